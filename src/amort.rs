@@ -0,0 +1,301 @@
+use crate::finance::{PaymentMode, TvmConfig};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Row, Table, TableState},
+};
+
+// 償還表
+// 符号は PMT と同じ向き (借入 PV > 0 なら支払・利息・元金は負)
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmortRow {
+    pub period: usize,
+    pub payment: f64,
+    pub interest: f64,
+    pub principal: f64,
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    pub title: String,
+    pub rows: Vec<AmortRow>,
+    // amort で指定した期の範囲 (1始まり)
+    pub start: usize,
+    pub end: usize,
+}
+
+// 償還表の期数の上限
+const MAX_PERIODS: f64 = 10_000.0;
+
+fn periods(n: f64) -> Result<usize, String> {
+    // 端数の期は1期として数える
+    match n.is_finite() && (1.0..=MAX_PERIODS).contains(&n) {
+        true => Ok(n.ceil() as usize),
+        false => Err(format!("N must be from 1 to {MAX_PERIODS} for a schedule")),
+    }
+}
+
+pub fn level_payment(
+    n: f64,
+    iyr: f64,
+    pv: f64,
+    pmt: f64,
+    config: &TvmConfig,
+) -> Result<Vec<AmortRow>, String> {
+    // 元利均等 (TVMの n, iyr, pv, pmt)
+    // 期末払いは前期末の残高に利息がつく。期首払いの第1回は利息なし
+    let i = config.periodic_rate(iyr);
+    let mut balance = pv;
+    let rows = (1..=periods(n)?)
+        .map(|period| {
+            let interest = match (config.mode, period) {
+                (PaymentMode::Begin, 1) => 0.0,
+                _ => -balance * i,
+            };
+            let principal = pmt - interest;
+            balance += principal;
+            AmortRow {
+                period,
+                payment: pmt,
+                interest,
+                principal,
+                balance,
+            }
+        })
+        .collect();
+    Ok(rows)
+}
+
+pub fn equal_principal(
+    n: f64,
+    iyr: f64,
+    pv: f64,
+    config: &TvmConfig,
+) -> Result<Vec<AmortRow>, String> {
+    // 元金均等 (TVMの n, iyr, pv。PMTは使わない)
+    // 毎期の元金は PV / n で一定、支払額は元金 + 残高に対する利息
+    let i = config.periodic_rate(iyr);
+    let last = periods(n)?;
+    let mut balance = pv;
+    let rows = (1..=last)
+        .map(|period| {
+            let interest = match (config.mode, period) {
+                (PaymentMode::Begin, 1) => 0.0,
+                _ => -balance * i,
+            };
+            // 最終回は端数を含めて残高を0にする
+            let principal = match period == last {
+                true => -balance,
+                false => -pv / n,
+            };
+            balance += principal;
+            AmortRow {
+                period,
+                payment: principal + interest,
+                interest,
+                principal,
+                balance,
+            }
+        })
+        .collect();
+    Ok(rows)
+}
+
+pub fn total_interest(rows: &[AmortRow]) -> f64 {
+    rows.iter().map(|row| row.interest).sum()
+}
+
+impl Schedule {
+    pub fn totals(&self) -> Result<(f64, f64, f64), String> {
+        // 範囲内の利息合計、元金合計、範囲末の残高
+        if self.start < 1 || self.start > self.end || self.end > self.rows.len() {
+            return Err(format!(
+                "Period range must be within 1..{}",
+                self.rows.len()
+            ));
+        }
+        let rows = &self.rows[self.start - 1..self.end];
+        let interest = rows.iter().map(|row| row.interest).sum();
+        let principal = rows.iter().map(|row| row.principal).sum();
+        Ok((interest, principal, rows[rows.len() - 1].balance))
+    }
+
+    pub fn csv(&self) -> String {
+        let lines = self
+            .rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "{},{},{},{},{}\n",
+                    row.period, row.payment, row.interest, row.principal, row.balance
+                )
+            })
+            .collect::<String>();
+        format!("period,payment,interest,principal,balance\n{lines}")
+    }
+}
+
+fn popup_area(area: Rect) -> Rect {
+    let [area] = Layout::vertical([Constraint::Percentage(80)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(90)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+pub fn run_table(
+    terminal: &mut ratatui::DefaultTerminal,
+    schedule: &Schedule,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    // ↑/↓:1行, PgUp/PgDn:10行, Home/End:先頭/末尾, c:CSVに書き出す, q/Esc:閉じる
+    // 書き出したときはそのメッセージを返す
+    let mut state = TableState::default().with_selected(schedule.start.saturating_sub(1));
+    let mut message: Option<String> = None;
+    let last = schedule.rows.len().saturating_sub(1);
+    loop {
+        terminal.draw(|frame| {
+            let area = popup_area(frame.area());
+            let rows = schedule.rows.iter().map(|row| {
+                let style = match (schedule.start..=schedule.end).contains(&row.period) {
+                    true => Style::default().add_modifier(Modifier::BOLD),
+                    false => Style::default(),
+                };
+                Row::new([
+                    row.period.to_string(),
+                    format!("{:.2}", row.payment),
+                    format!("{:.2}", row.interest),
+                    format!("{:.2}", row.principal),
+                    format!("{:.2}", row.balance),
+                ])
+                .style(style)
+            });
+            let footer = match (&message, schedule.totals()) {
+                (Some(message), _) => format!(" {message} "),
+                (None, Ok((interest, principal, balance))) => format!(
+                    " {}-{}: Σinterest {interest:.2} Σprincipal {principal:.2} balance {balance:.2} ",
+                    schedule.start, schedule.end
+                ),
+                (None, Err(e)) => format!(" {e} "),
+            };
+            let table = Table::new(rows, [Constraint::Fill(1); 5])
+                .header(
+                    Row::new(["Period", "Payment", "Interest", "Principal", "Balance"])
+                        .style(Style::default().add_modifier(Modifier::BOLD)),
+                )
+                .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                .block(
+                    Block::default()
+                        .title(format!(" {} ", schedule.title))
+                        .title(" ↑/↓ PgUp/PgDn Home/End:Scroll c:CSV q:Close ")
+                        .title_bottom(footer)
+                        .borders(Borders::ALL),
+                );
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(table, area, &mut state);
+        })?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            let selected = state.selected().unwrap_or(0);
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Up => state.select_previous(),
+                KeyCode::Down => state.select_next(),
+                KeyCode::PageUp => state.select(Some(selected.saturating_sub(10))),
+                KeyCode::PageDown => state.select(Some((selected + 10).min(last))),
+                KeyCode::Home => state.select_first(),
+                KeyCode::End => state.select(Some(last)),
+                KeyCode::Char('c') => {
+                    // カレントディレクトリの amort.csv に書き出す
+                    // 既にあれば上書きせず amort-1.csv, amort-2.csv ... とする
+                    let dir = std::env::current_dir()?;
+                    let path = std::iter::once("amort.csv".to_string())
+                        .chain((1..).map(|k| format!("amort-{k}.csv")))
+                        .map(|name| dir.join(name))
+                        .find(|path| !path.exists())
+                        .unwrap_or_default();
+                    message = Some(match std::fs::write(&path, schedule.csv()) {
+                        Ok(()) => format!("Saved {}", path.display()),
+                        Err(e) => format!("Error: {e}"),
+                    });
+                }
+                _ => (),
+            }
+        }
+    }
+    terminal.clear()?;
+    Ok(message)
+}
+
+#[test]
+fn amort_test() {
+    use crate::finance;
+    let config = TvmConfig::default();
+    let pmt = finance::payment(12.0, 12.0, 1000.0, 0.0, &config);
+    let rows = level_payment(12.0, 12.0, 1000.0, pmt, &config).unwrap();
+    assert_eq!(rows.len(), 12);
+    assert!((rows[0].interest + 10.0).abs() < 1e-12);
+    assert!((rows[0].principal - (pmt + 10.0)).abs() < 1e-12);
+    assert!(rows[11].balance.abs() < 1e-9);
+    let level_interest = total_interest(&rows);
+    assert!((level_interest - (12.0 * pmt + 1000.0)).abs() < 1e-9);
+
+    let schedule = Schedule {
+        title: "test".to_string(),
+        rows,
+        start: 1,
+        end: 12,
+    };
+    let (interest, principal, balance) = schedule.totals().unwrap();
+    assert!((principal + 1000.0).abs() < 1e-9);
+    assert!((interest - level_interest).abs() < 1e-12);
+    assert!(balance.abs() < 1e-9);
+    assert!(
+        Schedule {
+            start: 3,
+            end: 13,
+            ..schedule.clone()
+        }
+        .totals()
+        .is_err()
+    );
+    let csv = schedule.csv();
+    assert_eq!(csv.lines().count(), 13);
+    assert!(csv.starts_with("period,payment,interest,principal,balance\n1,"));
+
+    // 期首払い: 第1回は利息なし
+    let begin = TvmConfig {
+        mode: PaymentMode::Begin,
+        ..config
+    };
+    let pmt = finance::payment(12.0, 12.0, 1000.0, 0.0, &begin);
+    let rows = level_payment(12.0, 12.0, 1000.0, pmt, &begin).unwrap();
+    assert_eq!(rows[0].interest, 0.0);
+    assert!((rows[1].interest + (1000.0 + pmt) * 0.01).abs() < 1e-12);
+    assert!(rows[11].balance.abs() < 1e-9);
+    // 期数は有限で上限以内
+    for n in [0.5, 10_001.0, 1e300, f64::INFINITY, f64::NAN] {
+        assert!(level_payment(n, 12.0, 1000.0, pmt, &config).is_err());
+    }
+
+    // 元金均等: 1000を12回、月1%
+    let rows = equal_principal(12.0, 12.0, 1000.0, &config).unwrap();
+    assert_eq!(rows.len(), 12);
+    assert!((rows[0].principal + 1000.0 / 12.0).abs() < 1e-12);
+    assert!((rows[0].payment + 1000.0 / 12.0 + 10.0).abs() < 1e-12);
+    assert!((rows[11].payment + 1000.0 / 12.0 * 1.01).abs() < 1e-9);
+    assert_eq!(rows[11].balance, 0.0);
+    // 利息合計 = i * PV (n + 1) / 2
+    assert!((total_interest(&rows) + 0.01 * 1000.0 * 6.5).abs() < 1e-9);
+    // 元利均等より利息が少ない
+    let pmt = finance::payment(12.0, 12.0, 1000.0, 0.0, &config);
+    let level = level_payment(12.0, 12.0, 1000.0, pmt, &config).unwrap();
+    assert!(total_interest(&rows) > total_interest(&level));
+    assert!(equal_principal(f64::INFINITY, 12.0, 1000.0, &config).is_err());
+}
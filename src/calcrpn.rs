@@ -1,4 +1,16 @@
+use crate::amort;
+use crate::cashflow;
+use crate::constants::{self, PhysConst};
+use crate::currency;
+use crate::distrib;
 use crate::finance;
+use crate::numeric;
+use crate::numtheory;
+use crate::poly;
+use crate::random::{self, Rng};
+use crate::special;
+use crate::stats::{self, Fit, FitModel, StatData};
+use crate::symbolic::{self, Sym};
 use core::f64;
 use num::complex::Complex;
 use std::collections::BTreeMap;
@@ -19,6 +31,19 @@ pub enum Expr {
     Const(Constant),
     Opstack(OperateStack),
     Memo(Memorize),
+    Stat(Statistics),
+    Dist(Distribution, DistFunc),
+    Random(Random),
+    Integer(NumberTheory),
+    List(Vec<CalcNum>),
+    Poly(Polynomial),
+    Polar(f64, CalcNum),
+    Program(String),
+    Equation(Equation),
+    Algebra(Algebra),
+    Currency(Currency),
+    CashFlow(CashFlow),
+    Loan(Loan),
 }
 // 二項演算の列挙型
 #[derive(Debug)]
@@ -31,7 +56,18 @@ pub enum BinomialFunc {
     Pow,
     NCr,
     NPr,
+    Beta,
+    BesselJ,
+    BesselY,
 }
+// 演算子ではなく入力の書式や main.rs で処理するコマンドのヘルプ
+const SYNTAX_HELP: [&str; 5] = [
+    "\"x 2 ^ 2 -\" : store RPN program as equation f(x) (x is recalled from memo)",
+    "'x^2+1' : push symbolic expression (+ - * / ^ and functions build expressions)",
+    "100usd, 1500jpy, $100, ¥1500, €20 : currency amount (USD JPY EUR GBP CNY KRW ...)",
+    "rate : rate usd jpy 150.2 -> 1 USD = 150.2 JPY (rate : show rates)",
+    "cf : open cash flow worksheet",
+];
 pub fn print_help() {
     let allhelp = vec![
         BinomialFunc::show_help(),
@@ -40,6 +76,17 @@ pub fn print_help() {
         OperateStack::show_help(),
         DegMode::show_help(),
         Memorize::show_help(),
+        Statistics::show_help(),
+        Distribution::show_help(),
+        Random::show_help(),
+        NumberTheory::show_help(),
+        Polynomial::show_help(),
+        Equation::show_help(),
+        Algebra::show_help(),
+        Currency::show_help(),
+        CashFlow::show_help(),
+        Loan::show_help(),
+        SYNTAX_HELP.join("\n"),
     ];
     println!("calrpn");
     for help in allhelp {
@@ -57,6 +104,9 @@ impl Help for BinomialFunc {
             BinomialFunc::Pow => "3 2 ^ -> 3 ^ 2",
             BinomialFunc::NCr => "ncr: 10 2 ncr -> 10 nCr 2",
             BinomialFunc::NPr => "npr: 10 2 npr -> 10 nPr 2",
+            BinomialFunc::Beta => "beta: 2 3 beta -> B(2, 3)",
+            BinomialFunc::BesselJ => "besselj: 10 2 besselj -> J_2(10)",
+            BinomialFunc::BesselY => "bessely: 10 2 bessely -> Y_2(10)",
         }
     }
     fn show_help() -> String {
@@ -69,6 +119,9 @@ impl Help for BinomialFunc {
             BinomialFunc::Pow,
             BinomialFunc::NCr,
             BinomialFunc::NPr,
+            BinomialFunc::Beta,
+            BinomialFunc::BesselJ,
+            BinomialFunc::BesselY,
         ]
         .map(|x| x.help().to_string())
         .join("\n")
@@ -87,12 +140,30 @@ pub enum MonomialFunc {
     ASin,
     ACos,
     ATan,
+    Sinh,
+    Cosh,
+    Tanh,
+    ASinh,
+    ACosh,
+    ATanh,
     ToDeg,
     ToRad,
     Abs,
     Factorial,
+    Gamma,
+    LnGamma,
+    Erf,
+    Erfc,
+    EllipK,
+    EllipE,
+    Zeta,
     ToPolar,
     ToRec,
+    Re,
+    Im,
+    Conj,
+    Arg,
+    Cis,
 }
 impl Help for MonomialFunc {
     fn help(&self) -> &str {
@@ -106,12 +177,30 @@ impl Help for MonomialFunc {
             MonomialFunc::ASin => "asin: 30 asin -> asin(30)",
             MonomialFunc::ACos => "acos: 30 acos -> acos(30)",
             MonomialFunc::ATan => "atan: 30 atan -> atan(30)",
+            MonomialFunc::Sinh => "sinh: 2 sinh -> sinh(2)",
+            MonomialFunc::Cosh => "cosh: 2 cosh -> cosh(2)",
+            MonomialFunc::Tanh => "tanh: 2 tanh -> tanh(2)",
+            MonomialFunc::ASinh => "asinh: 2 asinh -> asinh(2)",
+            MonomialFunc::ACosh => "acosh: 2 acosh -> acosh(2)",
+            MonomialFunc::ATanh => "atanh: 0.5 atanh -> atanh(0.5)",
             MonomialFunc::ToDeg => "todeg: pi todeg -> pi to degrees",
             MonomialFunc::ToRad => "torad: 30 torad -> 30 to radians",
             MonomialFunc::Abs => "abs: 10+30i abs -> abs(10+30i)",
-            MonomialFunc::Factorial => "n! or ! : 10 ! -> factorial(10)",
-            MonomialFunc::ToPolar => "topolar: 30+2i topolar -> 30+2i to polar",
-            MonomialFunc::ToRec => "torec: 30+45i torec -> 30+45i to rectangular",
+            MonomialFunc::Factorial => "n! or ! : 10 ! -> factorial(10), 0.5 ! -> Γ(1.5)",
+            MonomialFunc::Gamma => "gamma: 4.5 gamma -> Γ(4.5)",
+            MonomialFunc::LnGamma => "lngamma: 100 lngamma -> ln Γ(100)",
+            MonomialFunc::Erf => "erf: 0.5 erf -> erf(0.5)",
+            MonomialFunc::Erfc => "erfc: 0.5 erfc -> erfc(0.5)",
+            MonomialFunc::EllipK => "ellipk: 0.5 ellipk -> K(m = 0.5)",
+            MonomialFunc::EllipE => "ellipe: 0.5 ellipe -> E(m = 0.5)",
+            MonomialFunc::Zeta => "zeta: 3 zeta -> ζ(3)",
+            MonomialFunc::ToPolar => "topolar: 3+4i topolar -> 5 (r), 53.13 (θ)",
+            MonomialFunc::ToRec => "torec: 5 30 torec -> 5∠30 to rectangular",
+            MonomialFunc::Re => "re: 3+4i re -> 3",
+            MonomialFunc::Im => "im: 3+4i im -> 4",
+            MonomialFunc::Conj => "conj: 3+4i conj -> 3-4i",
+            MonomialFunc::Arg => "arg: 3+4i arg -> 53.13 (angle of complex)",
+            MonomialFunc::Cis => "cis: 30 cis -> cos(30)+i sin(30)",
         }
     }
     fn show_help() -> String {
@@ -125,12 +214,30 @@ impl Help for MonomialFunc {
             MonomialFunc::ASin,
             MonomialFunc::ACos,
             MonomialFunc::ATan,
+            MonomialFunc::Sinh,
+            MonomialFunc::Cosh,
+            MonomialFunc::Tanh,
+            MonomialFunc::ASinh,
+            MonomialFunc::ACosh,
+            MonomialFunc::ATanh,
             MonomialFunc::ToDeg,
             MonomialFunc::ToRad,
             MonomialFunc::Abs,
             MonomialFunc::Factorial,
+            MonomialFunc::Gamma,
+            MonomialFunc::LnGamma,
+            MonomialFunc::Erf,
+            MonomialFunc::Erfc,
+            MonomialFunc::EllipK,
+            MonomialFunc::EllipE,
+            MonomialFunc::Zeta,
             MonomialFunc::ToPolar,
             MonomialFunc::ToRec,
+            MonomialFunc::Re,
+            MonomialFunc::Im,
+            MonomialFunc::Conj,
+            MonomialFunc::Arg,
+            MonomialFunc::Cis,
         ]
         .map(|x| x.help().to_string())
         .join("\n")
@@ -153,7 +260,7 @@ impl Help for Memorize {
             Memorize::Delete(_) => "mdel : delete memory",
             Memorize::Store(_) => "sto : store memory",
             Memorize::Tvm(_) => {
-                "tvm : calculate time value [n,iyr,pv,pmt,fv] // to memo -> tn,tiyr,tpv,tpmt,tfv"
+                "tvm : calculate time value [n,iyr,pv,pmt,fv] // to memo -> tn,tiyr,tpv,tpmt,tfv // payments/compounding per year -> tpyr,tcyr (default 12, C/YR = P/YR)"
             }
         }
     }
@@ -173,18 +280,26 @@ impl Help for Memorize {
 pub enum Constant {
     Pi,
     E,
+    Physical(&'static PhysConst),
 }
 impl Help for Constant {
     fn help(&self) -> &str {
         match self {
             Constant::Pi => "pi : 3.14159265358979323846",
             Constant::E => "e : 2.71828182845904523536",
+            Constant::Physical(_) => {
+                "_c, _h, _hbar, _kB, _NA, _G, _e, _me ... : physical constants (const : browse and search)"
+            }
         }
     }
     fn show_help() -> String {
-        [Constant::Pi, Constant::E]
-            .map(|x| x.help().to_string())
-            .join("\n")
+        [
+            Constant::Pi,
+            Constant::E,
+            Constant::Physical(&constants::CATALOG[0]),
+        ]
+        .map(|x| x.help().to_string())
+        .join("\n")
     }
 }
 
@@ -199,6 +314,14 @@ pub enum OperateStack {
     Sum,
     Deg,
     Rad,
+    Grad,
+    Turn,
+    SetMod,
+    ClearMod,
+    Polar,
+    Rect,
+    Begin,
+    End,
 }
 impl Help for OperateStack {
     fn help(&self) -> &str {
@@ -211,6 +334,16 @@ impl Help for OperateStack {
             OperateStack::Sum => "sum : sum all stack",
             OperateStack::Deg => "deg : set degree mode",
             OperateStack::Rad => "rad : set radian mode",
+            OperateStack::Grad => "grad : set gradian mode",
+            OperateStack::Turn => "turn : set turn mode",
+            OperateStack::SetMod => "setmod : 97 setmod -> + - * / ^ in Z/97Z",
+            OperateStack::ClearMod => "clmod : leave modular mode (or 0 setmod)",
+            OperateStack::Polar => "dpolar : show complex numbers as r∠θ (input 5∠30 or 5@30)",
+            OperateStack::Rect => "drect : show complex numbers as a+bi",
+            OperateStack::Begin => {
+                "begin : TVM payments at the beginning of each period (annuity due)"
+            }
+            OperateStack::End => "end : TVM payments at the end of each period (ordinary annuity)",
         }
     }
     fn show_help() -> String {
@@ -223,30 +356,496 @@ impl Help for OperateStack {
             OperateStack::Sum,
             OperateStack::Deg,
             OperateStack::Rad,
+            OperateStack::Grad,
+            OperateStack::Turn,
+            OperateStack::SetMod,
+            OperateStack::ClearMod,
+            OperateStack::Polar,
+            OperateStack::Rect,
+            OperateStack::Begin,
+            OperateStack::End,
         ]
         .map(|x| x.help().to_string())
         .join("\n")
     }
 }
-// 角度モードの列挙型
+// 統計処理の列挙型
+#[derive(Debug)]
+pub enum Statistics {
+    Add,
+    Add2,
+    Remove,
+    Remove2,
+    Clear,
+    Count,
+    Mean,
+    SDev,
+    PSDev,
+    Var,
+    PVar,
+    Median,
+    Min,
+    Max,
+    Fit(Option<FitModel>),
+    Corr,
+    RSquared,
+    YHat,
+    XHat,
+}
+impl Help for Statistics {
+    fn help(&self) -> &str {
+        match self {
+            Statistics::Add => "s+ or Σ+ : 3 s+ -> add x to stat data",
+            Statistics::Add2 => "s2+ or Σ2+ : 1 3 s2+ -> add (x, y) to stat data",
+            Statistics::Remove => "s- or Σ- : 3 s- -> remove x from stat data",
+            Statistics::Remove2 => "s2- or Σ2- : 1 3 s2- -> remove (x, y) from stat data",
+            Statistics::Clear => "scl or clΣ : clear stat data",
+            Statistics::Count => "count : number of data",
+            Statistics::Mean => "mean : mean of data",
+            Statistics::SDev => "sdev : sample standard deviation",
+            Statistics::PSDev => "psdev : population standard deviation",
+            Statistics::Var => "var : sample variance",
+            Statistics::PVar => "pvar : population variance",
+            Statistics::Median => "median : median of data",
+            Statistics::Min => "min : minimum of data",
+            Statistics::Max => "max : maximum of data  // stat data or stack if stat data is empty",
+            Statistics::Fit(Some(FitModel::Linear)) => "linfit : y = a + bx -> push a, b",
+            Statistics::Fit(Some(FitModel::Exponential)) => "expfit : y = a·e^(bx) -> push a, b",
+            Statistics::Fit(Some(FitModel::Logarithmic)) => "logfit : y = a + b·ln(x) -> push a, b",
+            Statistics::Fit(Some(FitModel::Power)) => "pwrfit : y = a·x^b -> push a, b",
+            Statistics::Fit(None) => {
+                "bestfit : fit with the largest |r|  // (x, y) stat data or x1 y1 x2 y2 .. stack"
+            }
+            Statistics::Corr => "corr : correlation coefficient r of last fit",
+            Statistics::RSquared => "r2 : coefficient of determination R² of last fit",
+            Statistics::YHat => "yhat or ŷ : 5 yhat -> estimate y at x = 5",
+            Statistics::XHat => "xhat or x̂ : 5 xhat -> estimate x at y = 5",
+        }
+    }
+    fn show_help() -> String {
+        [
+            Statistics::Add,
+            Statistics::Add2,
+            Statistics::Remove,
+            Statistics::Remove2,
+            Statistics::Clear,
+            Statistics::Count,
+            Statistics::Mean,
+            Statistics::SDev,
+            Statistics::PSDev,
+            Statistics::Var,
+            Statistics::PVar,
+            Statistics::Median,
+            Statistics::Min,
+            Statistics::Max,
+            Statistics::Fit(Some(FitModel::Linear)),
+            Statistics::Fit(Some(FitModel::Exponential)),
+            Statistics::Fit(Some(FitModel::Logarithmic)),
+            Statistics::Fit(Some(FitModel::Power)),
+            Statistics::Fit(None),
+            Statistics::Corr,
+            Statistics::RSquared,
+            Statistics::YHat,
+            Statistics::XHat,
+        ]
+        .map(|x| x.help().to_string())
+        .join("\n")
+    }
+}
+// 確率分布の列挙型
+#[derive(Debug)]
+pub enum Distribution {
+    Normal,
+    StudentT,
+    ChiSquare,
+    F,
+    Binomial,
+    Poisson,
+}
+// 確率分布の関数(確率密度・累積分布・逆関数)
+#[derive(Debug)]
+pub enum DistFunc {
+    Pdf,
+    Cdf,
+    Inv,
+}
+impl Distribution {
+    // スタックから取り出す引数の数
+    fn arity(&self) -> usize {
+        match self {
+            Distribution::Normal | Distribution::F | Distribution::Binomial => 3,
+            Distribution::StudentT | Distribution::ChiSquare | Distribution::Poisson => 2,
+        }
+    }
+}
+impl Help for Distribution {
+    fn help(&self) -> &str {
+        match self {
+            Distribution::Normal => {
+                "normpdf normcdf norminv : x μ σ normcdf -> P(X ≤ x), p μ σ norminv -> x"
+            }
+            Distribution::StudentT => "tpdf tcdf tinv : x ν tcdf -> P(T ≤ x), p ν tinv -> x",
+            Distribution::ChiSquare => "chi2pdf chi2cdf chi2inv : x k chi2cdf, p k chi2inv",
+            Distribution::F => "fpdf fcdf finv : x d1 d2 fcdf, p d1 d2 finv",
+            Distribution::Binomial => {
+                "binompdf binomcdf binominv : k n p binompdf -> P(X = k), q n p binominv -> k"
+            }
+            Distribution::Poisson => "poispdf poiscdf poisinv : k λ poiscdf, q λ poisinv",
+        }
+    }
+    fn show_help() -> String {
+        [
+            Distribution::Normal,
+            Distribution::StudentT,
+            Distribution::ChiSquare,
+            Distribution::F,
+            Distribution::Binomial,
+            Distribution::Poisson,
+        ]
+        .map(|x| x.help().to_string())
+        .join("\n")
+    }
+}
+// 乱数の列挙型
+#[derive(Debug)]
+pub enum Random {
+    Rand,
+    RandInt,
+    RandN,
+    Seed,
+    Dice(u32, u32),
+}
+impl Help for Random {
+    fn help(&self) -> &str {
+        match self {
+            Random::Rand => "rand : uniform random number in [0, 1)",
+            Random::RandInt => "randint : 1 6 randint -> random integer in [1, 6]",
+            Random::RandN => "randn : standard normal random number",
+            Random::Seed => "seed : 42 seed -> reset random sequence with seed 42",
+            Random::Dice(_, _) => "NdM : 3d6 -> sum of three 6-sided dice",
+        }
+    }
+    fn show_help() -> String {
+        [
+            Random::Rand,
+            Random::RandInt,
+            Random::RandN,
+            Random::Seed,
+            Random::Dice(3, 6),
+        ]
+        .map(|x| x.help().to_string())
+        .join("\n")
+    }
+}
+// 整数論の列挙型
+#[derive(Debug)]
+pub enum NumberTheory {
+    Gcd,
+    Lcm,
+    IsPrime,
+    NextPrime,
+    Factor,
+    PowMod,
+    ModInv,
+    Totient,
+}
+impl NumberTheory {
+    // スタックから取り出す引数の数
+    fn arity(&self) -> usize {
+        match self {
+            NumberTheory::IsPrime
+            | NumberTheory::NextPrime
+            | NumberTheory::Factor
+            | NumberTheory::Totient => 1,
+            NumberTheory::Gcd | NumberTheory::Lcm | NumberTheory::ModInv => 2,
+            NumberTheory::PowMod => 3,
+        }
+    }
+}
+impl Help for NumberTheory {
+    fn help(&self) -> &str {
+        match self {
+            NumberTheory::Gcd => "gcd : 12 18 gcd -> 6",
+            NumberTheory::Lcm => "lcm : 4 6 lcm -> 12",
+            NumberTheory::IsPrime => "isprime : 97 isprime -> 1 (prime) or 0",
+            NumberTheory::NextPrime => "nextprime : 100 nextprime -> 101",
+            NumberTheory::Factor => "factor : 120 factor -> 2 2 2 3 5 (2^3·3·5)",
+            NumberTheory::PowMod => "powmod : 4 13 497 powmod -> 4^13 mod 497",
+            NumberTheory::ModInv => "modinv : 3 11 modinv -> 3^-1 mod 11",
+            NumberTheory::Totient => "totient or phi : 36 totient -> φ(36)",
+        }
+    }
+    fn show_help() -> String {
+        [
+            NumberTheory::Gcd,
+            NumberTheory::Lcm,
+            NumberTheory::IsPrime,
+            NumberTheory::NextPrime,
+            NumberTheory::Factor,
+            NumberTheory::PowMod,
+            NumberTheory::ModInv,
+            NumberTheory::Totient,
+        ]
+        .map(|x| x.help().to_string())
+        .join("\n")
+    }
+}
+// 多項式の列挙型
+#[derive(Debug)]
+pub enum Polynomial {
+    Roots,
+    Eval,
+}
+impl Help for Polynomial {
+    fn help(&self) -> &str {
+        match self {
+            Polynomial::Roots => {
+                "proots : 1 0 -4 3 proots or [1 0 -4] proots -> roots of x^2-4 // [a b c] -> a b c 3"
+            }
+            Polynomial::Eval => "peval : [1 0 -4] 3 peval -> 3^2-4",
+        }
+    }
+    fn show_help() -> String {
+        [Polynomial::Roots, Polynomial::Eval]
+            .map(|x| x.help().to_string())
+            .join("\n")
+    }
+}
+// 登録した式("..."で囲んだRPNプログラム)に対する操作の列挙型
+#[derive(Debug)]
+pub enum Equation {
+    Solve,
+    BracketSolve,
+    Integrate,
+    Derivative,
+    Derivative2,
+    ComplexStep,
+    Plot,
+}
+impl Help for Equation {
+    fn help(&self) -> &str {
+        match self {
+            Equation::Solve => "solve : 1 solve -> root of f(x) = 0 near guess 1",
+            Equation::BracketSolve => "bsolve : 0 2 bsolve -> root of f(x) = 0 in [0, 2]",
+            Equation::Integrate => "integ : 0 1 integ -> error bound, ∫f(x)dx over [0, 1]",
+            Equation::Derivative => "deriv : 2 deriv -> f'(2)",
+            Equation::Derivative2 => "deriv2 : 2 deriv2 -> f''(2)",
+            Equation::ComplexStep => "cderiv : 2 cderiv -> f'(2) by complex step (Rad mode only)",
+            Equation::Plot => "plot : -5 5 plot -> graph of f(x) over [-5, 5]",
+        }
+    }
+    fn show_help() -> String {
+        [
+            Equation::Solve,
+            Equation::BracketSolve,
+            Equation::Integrate,
+            Equation::Derivative,
+            Equation::Derivative2,
+            Equation::ComplexStep,
+            Equation::Plot,
+        ]
+        .map(|x| x.help().to_string())
+        .join("\n")
+    }
+}
+// 記号式の操作の列挙型
+#[derive(Debug)]
+pub enum Algebra {
+    Simplify,
+    Expand,
+    Derivative,
+    Subst,
+}
+impl Help for Algebra {
+    fn help(&self) -> &str {
+        match self {
+            Algebra::Simplify => "simplify : 'x+x*1' simplify -> '2*x'",
+            Algebra::Expand => "expand : '(x+1)^2' expand -> 'x^2 + 2*x + 1'",
+            Algebra::Derivative => "d/dx : 'x^3' d/dx -> '3*x^2'",
+            Algebra::Subst => {
+                "subst : substitute memo values into expression (3 sto x 'x^2' subst -> 9)"
+            }
+        }
+    }
+    fn show_help() -> String {
+        [
+            Algebra::Simplify,
+            Algebra::Expand,
+            Algebra::Derivative,
+            Algebra::Subst,
+        ]
+        .map(|x| x.help().to_string())
+        .join("\n")
+    }
+}
+// 通貨の列挙型
+#[derive(Debug)]
+pub enum Currency {
+    Convert(&'static str),
+}
+impl Help for Currency {
+    fn help(&self) -> &str {
+        match self {
+            Currency::Convert(_) => ">jpy : 100usd >jpy -> convert to JPY with the rate table",
+        }
+    }
+    fn show_help() -> String {
+        [Currency::Convert("JPY")]
+            .map(|x| x.help().to_string())
+            .join("\n")
+    }
+}
+// キャッシュフローの列挙型
+#[derive(Debug)]
+pub enum CashFlow {
+    Initial,
+    Flow,
+    Count,
+    Npv,
+    Irr,
+    Mirr,
+    Payback,
+    DiscountedPayback,
+    Clear,
+}
+impl Help for CashFlow {
+    fn help(&self) -> &str {
+        match self {
+            CashFlow::Initial => "cf0 : -1000 cf0 -> start cash flows with CF0 = -1000",
+            CashFlow::Flow => "cfj : 300 cfj -> add next cash flow CFj",
+            CashFlow::Count => "nj : 3 nj -> repeat the last CFj 3 times",
+            CashFlow::Npv => "npv : 10 npv -> net present value at 10% per period",
+            CashFlow::Irr => "irr : internal rate of return (% per period)",
+            CashFlow::Mirr => "mirr : 10 12 mirr -> MIRR (finance 10%, reinvest 12%)",
+            CashFlow::Payback => "payback : periods until cumulative cash flow >= 0",
+            CashFlow::DiscountedPayback => "dpayback : 10 dpayback -> discounted payback at 10%",
+            CashFlow::Clear => "clcf : clear cash flows",
+        }
+    }
+    fn show_help() -> String {
+        [
+            CashFlow::Initial,
+            CashFlow::Flow,
+            CashFlow::Count,
+            CashFlow::Npv,
+            CashFlow::Irr,
+            CashFlow::Mirr,
+            CashFlow::Payback,
+            CashFlow::DiscountedPayback,
+            CashFlow::Clear,
+        ]
+        .map(|x| x.help().to_string())
+        .join("\n")
+    }
+}
+// 償還表の列挙型
 #[derive(Debug)]
+pub enum Loan {
+    Amortize,
+    EqualPrincipal,
+}
+impl Help for Loan {
+    fn help(&self) -> &str {
+        match self {
+            Loan::Amortize => {
+                "amort : 1 12 amort -> Σinterest Σprincipal balance for periods 1-12 (uses n,iyr,pv,pmt) and show schedule"
+            }
+            Loan::EqualPrincipal => {
+                "amortep : 1 12 amortep -> same as amort for equal principal repayment (元金均等, uses n,iyr,pv)"
+            }
+        }
+    }
+    fn show_help() -> String {
+        [Loan::Amortize, Loan::EqualPrincipal]
+            .map(|x| x.help().to_string())
+            .join("\n")
+    }
+}
+// 複素数の表示形式
+#[derive(Debug, Default, Clone, Copy)]
+pub enum ComplexForm {
+    #[default]
+    Rect,
+    Polar,
+}
+// 統計データなど、入力をまたいで保持するレジスタ
+#[derive(Debug, Default)]
+pub struct Registers {
+    pub stat: StatData,
+    pub fit: Option<Fit>,
+    pub rng: Rng,
+    pub modulus: Option<u64>,
+    pub complex_form: ComplexForm,
+    pub equation: Option<String>,
+    // グラフ表示の要求 (x の範囲)
+    pub plot: Option<(f64, f64)>,
+    // メッセージ欄に表示する計算結果の補足
+    pub notice: Option<String>,
+    // 通貨のレート表
+    pub rates: currency::RateTable,
+    // TVMの支払時期
+    pub payment_mode: finance::PaymentMode,
+    // キャッシュフロー表 (CF0, CFj, Nj)
+    pub cashflow: cashflow::CashFlows,
+    // 償還表の表示要求
+    pub schedule: Option<amort::Schedule>,
+}
+// 角度モードの列挙型
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DegMode {
     Rad,
     Deg,
+    Grad,
+    Turn,
 }
 impl Help for DegMode {
     fn help(&self) -> &str {
         match self {
-            DegMode::Rad => "Rad : Radian Mode",
-            DegMode::Deg => "Deg : Degree Mode",
+            DegMode::Rad => "Rad : Radian Mode (1.5rad : radian-tagged angle)",
+            DegMode::Deg => "Deg : Degree Mode (30° or 30deg : degree-tagged angle)",
+            DegMode::Grad => "Grad : Gradian Mode, 400 per turn (100grad : gradian-tagged angle)",
+            DegMode::Turn => "Turn : Turn Mode, 1 per turn (0.25turn : turn-tagged angle)",
         }
     }
     fn show_help() -> String {
-        [DegMode::Rad, DegMode::Deg]
+        [DegMode::Rad, DegMode::Deg, DegMode::Grad, DegMode::Turn]
             .map(|x| x.help().to_string())
             .join("\n")
     }
 }
+impl DegMode {
+    pub fn radians_of(&self, val: f64) -> f64 {
+        match self {
+            DegMode::Rad => val,
+            DegMode::Deg => val.to_radians(),
+            DegMode::Grad => val * f64::consts::PI / 200.0,
+            DegMode::Turn => val * f64::consts::TAU,
+        }
+    }
+    pub fn units_of(&self, val: f64) -> f64 {
+        match self {
+            DegMode::Rad => val,
+            DegMode::Deg => val.to_degrees(),
+            DegMode::Grad => val * 200.0 / f64::consts::PI,
+            DegMode::Turn => val / f64::consts::TAU,
+        }
+    }
+    fn convert(&self, val: f64, from: &DegMode) -> f64 {
+        // from の単位の値をこの単位に換算する
+        match self == from {
+            true => val,
+            false => self.units_of(from.radians_of(val)),
+        }
+    }
+    fn suffix(&self) -> &str {
+        match self {
+            DegMode::Rad => "rad",
+            DegMode::Deg => "°",
+            DegMode::Grad => "grad",
+            DegMode::Turn => "turn",
+        }
+    }
+}
 #[derive(Debug, Clone)]
 pub enum TvmItem {
     Value(f64),
@@ -262,6 +861,8 @@ impl TvmItem {
                     "pv" => Self::Value(n),
                     "pmt" => Self::Value(n),
                     "fv" => Self::Value(n),
+                    "pyr" => Self::Value(n),
+                    "cyr" => Self::Value(n),
                     _ => Self::None,
                 },
                 Err(_) => Self::None,
@@ -276,62 +877,125 @@ impl TvmItem {
             Self::None => None,
         }
     }
-    fn n_value(memory_map: &BTreeMap<String, CalcNum>) -> Option<f64> {
+    pub fn config(
+        memory_map: &BTreeMap<String, CalcNum>,
+        mode: finance::PaymentMode,
+    ) -> Option<finance::TvmConfig> {
+        // P/YR の既定値は12、C/YR の既定値は P/YR と同じ
+        let p_yr = Self::get_value(memory_map, "pyr").unwrap_or(12.0);
+        let c_yr = Self::get_value(memory_map, "cyr").unwrap_or(p_yr);
+        match p_yr > 0.0 && c_yr > 0.0 {
+            true => Some(finance::TvmConfig { p_yr, c_yr, mode }),
+            false => None,
+        }
+    }
+    fn n_value(memory_map: &BTreeMap<String, CalcNum>, mode: finance::PaymentMode) -> Option<f64> {
         let iyr = Self::get_value(memory_map, "iyr");
         let pv = Self::get_value(memory_map, "pv");
         let pmt = Self::get_value(memory_map, "pmt");
         let fv = Self::get_value(memory_map, "fv");
-        match (iyr, pv, pmt, fv) {
-            (Some(iyr), Some(pv), Some(pmt), Some(fv)) => {
-                Some(finance::number_of_periods(iyr, pv, pmt, fv))
+        match (iyr, pv, pmt, fv, Self::config(memory_map, mode)) {
+            (Some(iyr), Some(pv), Some(pmt), Some(fv), Some(config)) => {
+                Some(finance::number_of_periods(iyr, pv, pmt, fv, &config))
+                    .filter(|v| v.is_finite())
             }
-            (_, _, _, _) => None,
+            _ => None,
         }
     }
-    fn payment(memory_map: &BTreeMap<String, CalcNum>) -> Option<f64> {
+    fn payment(memory_map: &BTreeMap<String, CalcNum>, mode: finance::PaymentMode) -> Option<f64> {
         let iyr = Self::get_value(memory_map, "iyr");
         let pv = Self::get_value(memory_map, "pv");
         let n = Self::get_value(memory_map, "n");
         let fv = Self::get_value(memory_map, "fv");
-        match (iyr, pv, n, fv) {
-            (Some(iyr), Some(pv), Some(n), Some(fv)) => Some(finance::payment(n, iyr, pv, fv)),
-            (_, _, _, _) => None,
+        match (iyr, pv, n, fv, Self::config(memory_map, mode)) {
+            (Some(iyr), Some(pv), Some(n), Some(fv), Some(config)) => {
+                Some(finance::payment(n, iyr, pv, fv, &config)).filter(|v| v.is_finite())
+            }
+            _ => None,
         }
     }
-    fn present_value(memory_map: &BTreeMap<String, CalcNum>) -> Option<f64> {
+    fn present_value(
+        memory_map: &BTreeMap<String, CalcNum>,
+        mode: finance::PaymentMode,
+    ) -> Option<f64> {
         let iyr = Self::get_value(memory_map, "iyr");
         let pmt = Self::get_value(memory_map, "pmt");
         let n = Self::get_value(memory_map, "n");
         let fv = Self::get_value(memory_map, "fv");
-        match (iyr, pmt, n, fv) {
-            (Some(iyr), Some(pmt), Some(n), Some(fv)) => {
-                Some(finance::present_value(n, iyr, pmt, fv))
+        match (iyr, pmt, n, fv, Self::config(memory_map, mode)) {
+            (Some(iyr), Some(pmt), Some(n), Some(fv), Some(config)) => {
+                Some(finance::present_value(n, iyr, pmt, fv, &config)).filter(|v| v.is_finite())
             }
-            (_, _, _, _) => None,
+            _ => None,
         }
     }
 
-    fn i_year_rate(memory_map: &BTreeMap<String, CalcNum>) -> Option<f64> {
+    fn i_year_rate(
+        memory_map: &BTreeMap<String, CalcNum>,
+        mode: finance::PaymentMode,
+    ) -> Result<finance::RateReport, String> {
         let pv = Self::get_value(memory_map, "pv");
         let pmt = Self::get_value(memory_map, "pmt");
         let n = Self::get_value(memory_map, "n");
         let fv = Self::get_value(memory_map, "fv");
-        match (pv, pmt, n, fv) {
-            (Some(pv), Some(pmt), Some(n), Some(fv)) => finance::i_yr(n, pv, pmt, fv),
-            (_, _, _, _) => None,
+        match (pv, pmt, n, fv, Self::config(memory_map, mode)) {
+            (Some(pv), Some(pmt), Some(n), Some(fv), Some(config)) => {
+                finance::i_yr(n, pv, pmt, fv, &config)
+            }
+            _ => Err("i_year Error".to_string()),
+        }
+    }
+
+    fn schedule(
+        memory_map: &BTreeMap<String, CalcNum>,
+        mode: finance::PaymentMode,
+    ) -> Result<Vec<amort::AmortRow>, String> {
+        let n = Self::get_value(memory_map, "n");
+        let iyr = Self::get_value(memory_map, "iyr");
+        let pv = Self::get_value(memory_map, "pv");
+        let pmt = Self::get_value(memory_map, "pmt");
+        match (n, iyr, pv, pmt, Self::config(memory_map, mode)) {
+            (Some(n), Some(iyr), Some(pv), Some(pmt), Some(config)) => {
+                amort::level_payment(n, iyr, pv, pmt, &config)
+            }
+            _ => Err("amort needs n, iyr, pv and pmt".to_string()),
+        }
+    }
+
+    fn equal_principal_schedule(
+        memory_map: &BTreeMap<String, CalcNum>,
+        mode: finance::PaymentMode,
+    ) -> Result<(Vec<amort::AmortRow>, f64), String> {
+        // 元金均等の償還表と、同じ条件の元利均等での利息合計 (比較用)
+        let n = Self::get_value(memory_map, "n");
+        let iyr = Self::get_value(memory_map, "iyr");
+        let pv = Self::get_value(memory_map, "pv");
+        match (n, iyr, pv, Self::config(memory_map, mode)) {
+            (Some(n), Some(iyr), Some(pv), Some(config)) => {
+                let pmt = finance::payment(n, iyr, pv, 0.0, &config);
+                let level = amort::level_payment(n, iyr, pv, pmt, &config)?;
+                Ok((
+                    amort::equal_principal(n, iyr, pv, &config)?,
+                    amort::total_interest(&level),
+                ))
+            }
+            _ => Err("amortep needs n, iyr and pv".to_string()),
         }
     }
 
-    fn future_value(memory_map: &BTreeMap<String, CalcNum>) -> Option<f64> {
+    fn future_value(
+        memory_map: &BTreeMap<String, CalcNum>,
+        mode: finance::PaymentMode,
+    ) -> Option<f64> {
         let iyr = Self::get_value(memory_map, "iyr");
         let pmt = Self::get_value(memory_map, "pmt");
         let n = Self::get_value(memory_map, "n");
         let pv = Self::get_value(memory_map, "pv");
-        match (iyr, pmt, n, pv) {
-            (Some(iyr), Some(pmt), Some(n), Some(pv)) => {
-                Some(finance::future_value(n, iyr, pv, pmt))
+        match (iyr, pmt, n, pv, Self::config(memory_map, mode)) {
+            (Some(iyr), Some(pmt), Some(n), Some(pv), Some(config)) => {
+                Some(finance::future_value(n, iyr, pv, pmt, &config)).filter(|v| v.is_finite())
             }
-            (_, _, _, _) => None,
+            _ => None,
         }
     }
 }
@@ -339,10 +1003,23 @@ impl TvmItem {
 pub enum CalcNum {
     Number(f64),
     Complex(Complex<f64>),
+    Symbolic(Sym),
+    // 入力時(または逆三角関数の結果)の単位を覚えている角度
+    Angle(f64, DegMode),
+    // 通貨コード付きの金額
+    Money(f64, &'static str),
+    // f64で正確に表せない大きな整数 (整数論の入出力)
+    Integer(i128),
 }
 impl FromStr for CalcNum {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // 2^53 を超える整数リテラルは丸めずに保持する
+        if let Ok(val) = s.parse::<i128>()
+            && val.unsigned_abs() > MAX_EXACT_INTEGER as u128
+        {
+            return Ok(CalcNum::Integer(val));
+        }
         match s.parse::<f64>() {
             Ok(val) => Ok(CalcNum::Number(val)),
             Err(_) => match s.parse::<Complex<f64>>() {
@@ -358,11 +1035,27 @@ impl Add for CalcNum {
     // 加算
     type Output = Self;
     fn add(self, other: Self) -> Self {
+        if let Some((a, b)) = CalcNum::integer_operands(&self, &other)
+            && let Some(val) = a.checked_add(b)
+        {
+            return exact_integer(val);
+        }
+        if let Some((a, b, code)) = CalcNum::money_operands(&self, &other) {
+            return CalcNum::Money(a + b, code);
+        }
+        if let Some((a, b, unit)) = CalcNum::angle_operands(&self, &other) {
+            return CalcNum::Angle(a + b, unit);
+        }
         match (self, other) {
+            (a @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_)), b)
+            | (a, b @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_))) => {
+                a.untagged() + b.untagged()
+            }
             (CalcNum::Number(a), CalcNum::Number(b)) => CalcNum::Number(a + b),
             (CalcNum::Complex(a), CalcNum::Complex(b)) => CalcNum::Complex(a + b),
             (CalcNum::Number(a), CalcNum::Complex(b)) => CalcNum::Complex(Complex::new(a, 0.0) + b),
             (CalcNum::Complex(a), CalcNum::Number(b)) => CalcNum::Complex(a + Complex::new(b, 0.0)),
+            (a, b) => CalcNum::symbolic_binary(&a, &b, |a, b| a + b),
         }
     }
 }
@@ -371,11 +1064,27 @@ impl Sub for CalcNum {
     // 引き算
     type Output = Self;
     fn sub(self, other: Self) -> Self {
+        if let Some((a, b)) = CalcNum::integer_operands(&self, &other)
+            && let Some(val) = a.checked_sub(b)
+        {
+            return exact_integer(val);
+        }
+        if let Some((a, b, code)) = CalcNum::money_operands(&self, &other) {
+            return CalcNum::Money(a - b, code);
+        }
+        if let Some((a, b, unit)) = CalcNum::angle_operands(&self, &other) {
+            return CalcNum::Angle(a - b, unit);
+        }
         match (self, other) {
+            (a @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_)), b)
+            | (a, b @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_))) => {
+                a.untagged() - b.untagged()
+            }
             (CalcNum::Number(a), CalcNum::Number(b)) => CalcNum::Number(a - b),
             (CalcNum::Complex(a), CalcNum::Complex(b)) => CalcNum::Complex(a - b),
             (CalcNum::Number(a), CalcNum::Complex(b)) => CalcNum::Complex(Complex::new(a, 0.0) - b),
             (CalcNum::Complex(a), CalcNum::Number(b)) => CalcNum::Complex(a - Complex::new(b, 0.0)),
+            (a, b) => CalcNum::symbolic_binary(&a, &b, |a, b| a - b),
         }
     }
 }
@@ -383,11 +1092,32 @@ impl Mul for CalcNum {
     // 乗算
     type Output = Self;
     fn mul(self, other: Self) -> Self {
+        if let Some((a, b)) = CalcNum::integer_operands(&self, &other)
+            && let Some(val) = a.checked_mul(b)
+        {
+            return exact_integer(val);
+        }
+        // 金額×数値は金額
+        if let Some((a, b, code)) = CalcNum::money_operands(&self, &other) {
+            return CalcNum::Money(a * b, code);
+        }
+        // 角度×数値は角度、角度×角度は数値
+        if let Some((a, b, unit)) = CalcNum::angle_operands(&self, &other) {
+            return match (&self, &other) {
+                (CalcNum::Angle(..), CalcNum::Angle(..)) => CalcNum::Number(a * b),
+                _ => CalcNum::Angle(a * b, unit),
+            };
+        }
         match (self, other) {
+            (a @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_)), b)
+            | (a, b @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_))) => {
+                a.untagged() * b.untagged()
+            }
             (CalcNum::Number(a), CalcNum::Number(b)) => CalcNum::Number(a * b),
             (CalcNum::Complex(a), CalcNum::Complex(b)) => CalcNum::Complex(a * b),
             (CalcNum::Number(a), CalcNum::Complex(b)) => CalcNum::Complex(Complex::new(a, 0.0) * b),
             (CalcNum::Complex(a), CalcNum::Number(b)) => CalcNum::Complex(a * Complex::new(b, 0.0)),
+            (a, b) => CalcNum::symbolic_binary(&a, &b, |a, b| a * b),
         }
     }
 }
@@ -396,11 +1126,37 @@ impl Div for CalcNum {
     // 除算
     type Output = Self;
     fn div(self, other: Self) -> Self {
+        // 大きな整数は割り切れるときだけ整数のまま
+        if let Some((a, b)) = CalcNum::integer_operands(&self, &other)
+            && b != 0
+            && a % b == 0
+        {
+            return exact_integer(a / b);
+        }
+        // 金額÷数値は金額、同じ通貨の金額どうしの比は数値
+        if let Some((a, b, code)) = CalcNum::money_operands(&self, &other) {
+            return match (&self, &other) {
+                (CalcNum::Money(..), CalcNum::Money(..)) => CalcNum::Number(a / b),
+                _ => CalcNum::Money(a / b, code),
+            };
+        }
+        // 角度÷数値は角度、角度÷角度(単位を揃えた比)と数値÷角度は数値
+        if let Some((a, b, unit)) = CalcNum::angle_operands(&self, &other) {
+            return match (&self, &other) {
+                (CalcNum::Angle(..), CalcNum::Number(_)) => CalcNum::Angle(a / b, unit),
+                _ => CalcNum::Number(a / b),
+            };
+        }
         match (self, other) {
+            (a @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_)), b)
+            | (a, b @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_))) => {
+                a.untagged() / b.untagged()
+            }
             (CalcNum::Number(a), CalcNum::Number(b)) => CalcNum::Number(a / b),
             (CalcNum::Complex(a), CalcNum::Complex(b)) => CalcNum::Complex(a / b),
             (CalcNum::Number(a), CalcNum::Complex(b)) => CalcNum::Complex(Complex::new(a, 0.0) / b),
             (CalcNum::Complex(a), CalcNum::Number(b)) => CalcNum::Complex(a / Complex::new(b, 0.0)),
+            (a, b) => CalcNum::symbolic_binary(&a, &b, |a, b| a / b),
         }
     }
 }
@@ -408,11 +1164,28 @@ impl Rem for CalcNum {
     // 余算
     type Output = Self;
     fn rem(self, other: Self) -> Self {
+        if let Some((a, b)) = CalcNum::integer_operands(&self, &other)
+            && let Some(val) = a.checked_rem(b)
+        {
+            return exact_integer(val);
+        }
+        if let Some((a, b, code)) = CalcNum::money_operands(&self, &other) {
+            return CalcNum::Money(a % b, code);
+        }
+        if let Some((a, b, unit)) = CalcNum::angle_operands(&self, &other) {
+            return CalcNum::Angle(a % b, unit);
+        }
         match (self, other) {
+            (a @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_)), b)
+            | (a, b @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_))) => {
+                a.untagged() % b.untagged()
+            }
             (CalcNum::Number(a), CalcNum::Number(b)) => CalcNum::Number(a % b),
             (CalcNum::Complex(a), CalcNum::Complex(b)) => CalcNum::Complex(a % b),
             (CalcNum::Number(a), CalcNum::Complex(b)) => CalcNum::Complex(Complex::new(a, 0.0) % b),
             (CalcNum::Complex(a), CalcNum::Number(b)) => CalcNum::Complex(a % Complex::new(b, 0.0)),
+            // 記号式の剰余は定義しない (manage_binomialでエラーにする)
+            _ => CalcNum::Number(f64::NAN),
         }
     }
 }
@@ -424,29 +1197,166 @@ impl CalcNum {
                 true => format!("{val:.0}"),
                 false => format!("{val:.n_place$}"),
             },
-            CalcNum::Complex(val) => {
-                format!("{:.2$}  i:{:.2$}", val.re, val.im, n_place)
+            CalcNum::Complex(val) => format!("{:.2$}{:+.2$}i", val.re, val.im, n_place),
+            CalcNum::Symbolic(sym) => format!("'{sym}'"),
+            CalcNum::Angle(val, unit) => {
+                format!(
+                    "{}{}",
+                    CalcNum::Number(*val).num_format(n_place),
+                    unit.suffix()
+                )
+            }
+            CalcNum::Money(val, code) => format!("{val:.0$} {code}", currency::decimals(code)),
+            CalcNum::Integer(val) => val.to_string(),
+        }
+    }
+
+    pub fn display(&self, n_place: usize, form: ComplexForm, degmode: &DegMode) -> String {
+        // 極形式の表示モードでは複素数を r∠θ (θは角度モードの単位) で表示する
+        match (self, form) {
+            (CalcNum::Complex(val), ComplexForm::Polar) => {
+                let (r, theta) = val.to_polar();
+                let theta = CalcNum::Angle(degmode.units_of(theta), *degmode);
+                format!("{r:.n_place$}∠{}", theta.num_format(n_place))
             }
+            _ => self.num_format(n_place),
         }
     }
 
     fn is_realnumber(&self) -> bool {
         // 実数チェック
-        matches!(self, CalcNum::Number(_))
+        matches!(
+            self,
+            CalcNum::Number(_) | CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_)
+        )
+    }
+
+    fn untagged(&self) -> CalcNum {
+        // 角度の単位や通貨を外して数値として扱う (大きな整数は丸める)
+        match self {
+            CalcNum::Angle(val, _) | CalcNum::Money(val, _) => CalcNum::Number(*val),
+            CalcNum::Integer(val) => CalcNum::Number(*val as f64),
+            _ => self.clone(),
+        }
+    }
+
+    fn radians(&self, degmode: &DegMode) -> Result<f64, Box<dyn std::error::Error>> {
+        // 角度をラジアンに換算する。単位がなければ角度モードの単位とみなす
+        match self {
+            CalcNum::Angle(val, unit) => Ok(unit.radians_of(*val)),
+            _ => Ok(degmode.radians_of(self.get_realnumber()?)),
+        }
     }
 
     fn is_integer(&self) -> bool {
         // 整数チェック
         match self {
-            CalcNum::Number(val) => val.fract() == 0.0,
+            CalcNum::Number(val) | CalcNum::Angle(val, _) | CalcNum::Money(val, _) => {
+                val.fract() == 0.0
+            }
+            CalcNum::Integer(_) => true,
             _ => false,
         }
     }
 
-    fn get_realnumber(&self) -> Result<f64, Box<dyn std::error::Error>> {
+    pub fn get_realnumber(&self) -> Result<f64, Box<dyn std::error::Error>> {
         match self {
-            CalcNum::Number(val) => Ok(*val),
+            CalcNum::Number(val) | CalcNum::Angle(val, _) | CalcNum::Money(val, _) => Ok(*val),
+            CalcNum::Integer(val) => Ok(*val as f64),
             CalcNum::Complex(_val) => Err("Complex number is not supported".into()),
+            CalcNum::Symbolic(_) => Err("Symbolic value is not supported".into()),
+        }
+    }
+    fn is_symbolic(&self) -> bool {
+        matches!(self, CalcNum::Symbolic(_))
+    }
+    fn to_symbolic(&self) -> Result<Sym, String> {
+        // 記号式に変換する。複素数は記号式では扱わない
+        match self {
+            CalcNum::Number(val) => Ok(Sym::Num(*val)),
+            // 記号式の角度はラジアン
+            CalcNum::Angle(val, unit) => Ok(Sym::Num(unit.radians_of(*val))),
+            CalcNum::Money(val, _) => Ok(Sym::Num(*val)),
+            CalcNum::Integer(val) => Ok(Sym::Num(*val as f64)),
+            CalcNum::Symbolic(sym) => Ok(sym.clone()),
+            CalcNum::Complex(_) => Err("Complex number is not supported in symbolic".to_string()),
+        }
+    }
+    fn angle_operands(a: &Self, b: &Self) -> Option<(f64, f64, DegMode)> {
+        // 角度を含む実数どうしの演算は、左側(なければ角度の方)の単位に揃える
+        match (a, b) {
+            (CalcNum::Angle(x, unit), CalcNum::Angle(y, other)) => {
+                Some((*x, unit.convert(*y, other), *unit))
+            }
+            (CalcNum::Angle(x, unit), CalcNum::Number(y)) => Some((*x, *y, *unit)),
+            (CalcNum::Number(x), CalcNum::Angle(y, unit)) => Some((*x, *y, *unit)),
+            _ => None,
+        }
+    }
+    fn integer_operands(a: &Self, b: &Self) -> Option<(i128, i128)> {
+        // 大きな整数を含む整数どうしの演算は i128 で正確に行う (あふれたら f64)
+        let exact = |x: &Self| match x {
+            CalcNum::Integer(val) => Some(*val),
+            CalcNum::Number(val) if val.fract() == 0.0 && val.abs() <= MAX_EXACT_INTEGER => {
+                Some(*val as i128)
+            }
+            _ => None,
+        };
+        match (a, b) {
+            (CalcNum::Integer(_), _) | (_, CalcNum::Integer(_)) => Some((exact(a)?, exact(b)?)),
+            _ => None,
+        }
+    }
+    fn money_operands(a: &Self, b: &Self) -> Option<(f64, f64, &'static str)> {
+        // 同じ通貨どうし、または金額と数値の演算 (それ以外はmanage_binomialでエラー)
+        match (a, b) {
+            (CalcNum::Money(x, code), CalcNum::Money(y, other)) if code == other => {
+                Some((*x, *y, code))
+            }
+            (CalcNum::Money(x, code), CalcNum::Number(y))
+            | (CalcNum::Number(x), CalcNum::Money(y, code)) => Some((*x, *y, code)),
+            _ => None,
+        }
+    }
+    fn currency_check(func: &BinomialFunc, a: &Self, b: &Self) -> Result<(), String> {
+        // 金額を含む二項演算で意味のないものを弾く
+        match (func, a, b) {
+            (_, CalcNum::Money(_, code), CalcNum::Money(_, other)) if code != other => Err(
+                format!("Currency mismatch {code}/{other} (convert with >{code})"),
+            ),
+            (
+                BinomialFunc::Add | BinomialFunc::Subtract | BinomialFunc::Mod,
+                CalcNum::Money(..),
+                CalcNum::Money(..),
+            )
+            | (BinomialFunc::Divide, CalcNum::Money(..), CalcNum::Money(..) | CalcNum::Number(_))
+            | (
+                BinomialFunc::Multiply | BinomialFunc::Mod,
+                CalcNum::Money(..),
+                CalcNum::Number(_),
+            )
+            | (
+                BinomialFunc::Add | BinomialFunc::Subtract | BinomialFunc::Multiply,
+                CalcNum::Number(_),
+                CalcNum::Money(..),
+            ) => Ok(()),
+            (_, CalcNum::Money(..), _) | (_, _, CalcNum::Money(..)) => {
+                Err("Unsupported operation for currency".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+    fn symbolic_binary(a: &Self, b: &Self, build: fn(Sym, Sym) -> Sym) -> CalcNum {
+        // 記号式を含む二項演算は式の木を作る
+        match (a.to_symbolic(), b.to_symbolic()) {
+            (Ok(a), Ok(b)) => CalcNum::Symbolic(build(a, b)),
+            _ => CalcNum::Number(f64::NAN),
+        }
+    }
+    fn symbolic_func(&self, name: &str) -> CalcNum {
+        match self {
+            CalcNum::Symbolic(sym) => CalcNum::Symbolic(Sym::func(name, sym.clone())),
+            _ => self.clone(),
         }
     }
     fn pow(&self, n: &Self) -> CalcNum {
@@ -457,18 +1367,29 @@ impl CalcNum {
                 CalcNum::Complex(Complex::new(*val, 0.0).powc(*n))
             }
             (CalcNum::Complex(val), CalcNum::Complex(n)) => CalcNum::Complex(val.powc(*n)),
+            (a @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_)), n)
+            | (a, n @ (CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_))) => {
+                a.untagged().pow(&n.untagged())
+            }
+            (a, n) => CalcNum::symbolic_binary(a, n, Sym::pow),
         }
     }
     fn log10(&self) -> CalcNum {
         match self {
             CalcNum::Number(val) => CalcNum::Number(val.log10()),
             CalcNum::Complex(val) => CalcNum::Complex(val.log10()),
+            CalcNum::Symbolic(_) => self.symbolic_func("log"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().log10()
+            }
         }
     }
     fn ln(&self) -> CalcNum {
         match self {
             CalcNum::Number(val) => CalcNum::Number(val.ln()),
             CalcNum::Complex(val) => CalcNum::Complex(val.ln()),
+            CalcNum::Symbolic(_) => self.symbolic_func("ln"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => self.untagged().ln(),
         }
     }
 
@@ -482,119 +1403,292 @@ impl CalcNum {
                 }
             }
             CalcNum::Complex(val) => CalcNum::Complex(val.sqrt()),
+            CalcNum::Symbolic(_) => self.symbolic_func("sqrt"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => self.untagged().sqrt(),
         }
     }
 
     fn sin(&self, degmode: &DegMode) -> CalcNum {
         match self {
-            CalcNum::Number(val) => CalcNum::Number(match degmode {
-                DegMode::Deg => val.to_radians().sin(),
-                DegMode::Rad => val.sin(),
-            }),
+            CalcNum::Number(val) => CalcNum::Number(degmode.radians_of(*val).sin()),
+            CalcNum::Angle(val, unit) => CalcNum::Number(unit.radians_of(*val).sin()),
+            CalcNum::Money(..) | CalcNum::Integer(_) => self.untagged().sin(degmode),
             CalcNum::Complex(val) => CalcNum::Complex(val.sin()),
+            CalcNum::Symbolic(_) => self.symbolic_func("sin"),
         }
     }
     fn cos(&self, degmode: &DegMode) -> CalcNum {
         match self {
-            CalcNum::Number(val) => CalcNum::Number(match degmode {
-                DegMode::Deg => val.to_radians().cos(),
-                DegMode::Rad => val.cos(),
-            }),
+            CalcNum::Number(val) => CalcNum::Number(degmode.radians_of(*val).cos()),
+            CalcNum::Angle(val, unit) => CalcNum::Number(unit.radians_of(*val).cos()),
+            CalcNum::Money(..) | CalcNum::Integer(_) => self.untagged().cos(degmode),
             CalcNum::Complex(val) => CalcNum::Complex(val.cos()),
+            CalcNum::Symbolic(_) => self.symbolic_func("cos"),
         }
     }
     fn tan(&self, degmode: &DegMode) -> CalcNum {
         match self {
-            CalcNum::Number(val) => CalcNum::Number(match degmode {
-                DegMode::Deg => val.to_radians().tan(),
-                DegMode::Rad => val.tan(),
-            }),
+            CalcNum::Number(val) => CalcNum::Number(degmode.radians_of(*val).tan()),
+            CalcNum::Angle(val, unit) => CalcNum::Number(unit.radians_of(*val).tan()),
+            CalcNum::Money(..) | CalcNum::Integer(_) => self.untagged().tan(degmode),
             CalcNum::Complex(val) => CalcNum::Complex(val.tan()),
+            CalcNum::Symbolic(_) => self.symbolic_func("tan"),
         }
     }
     fn asin(&self, degmode: &DegMode) -> CalcNum {
         match self {
-            CalcNum::Number(val) => CalcNum::Number(match degmode {
-                DegMode::Deg => val.asin().to_degrees(),
-                DegMode::Rad => val.asin(),
-            }),
+            CalcNum::Number(val) => CalcNum::Angle(degmode.units_of(val.asin()), *degmode),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().asin(degmode)
+            }
             CalcNum::Complex(val) => CalcNum::Complex(val.asin()),
+            CalcNum::Symbolic(_) => self.symbolic_func("asin"),
         }
     }
     fn acos(&self, degmode: &DegMode) -> CalcNum {
         match self {
-            CalcNum::Number(val) => CalcNum::Number(match degmode {
-                DegMode::Deg => val.acos().to_degrees(),
-                DegMode::Rad => val.acos(),
-            }),
+            CalcNum::Number(val) => CalcNum::Angle(degmode.units_of(val.acos()), *degmode),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().acos(degmode)
+            }
             CalcNum::Complex(val) => CalcNum::Complex(val.acos()),
+            CalcNum::Symbolic(_) => self.symbolic_func("acos"),
         }
     }
     fn atan(&self, degmode: &DegMode) -> CalcNum {
         match self {
-            CalcNum::Number(val) => CalcNum::Number(match degmode {
-                DegMode::Deg => val.atan().to_degrees(),
-                DegMode::Rad => val.atan(),
-            }),
+            CalcNum::Number(val) => CalcNum::Angle(degmode.units_of(val.atan()), *degmode),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().atan(degmode)
+            }
             CalcNum::Complex(val) => CalcNum::Complex(val.atan()),
+            CalcNum::Symbolic(_) => self.symbolic_func("atan"),
         }
     }
 
-    fn to_polar(&self, degmode: &DegMode) -> Result<CalcNum, Box<dyn std::error::Error>> {
-        // 極座標変換
+    fn sinh(&self) -> CalcNum {
         match self {
-            CalcNum::Number(_val) => Err("Invalid Type".into()),
-            CalcNum::Complex(val) => {
-                let result = val.to_polar();
-                let angle = match degmode {
-                    DegMode::Deg => CalcNum::Number(result.1).to_deg()?,
-                    DegMode::Rad => CalcNum::Number(result.1),
-                };
-                Ok(CalcNum::Complex(Complex {
-                    re: result.0,
-                    im: angle.get_realnumber()?,
-                }))
+            CalcNum::Number(val) => CalcNum::Number(val.sinh()),
+            CalcNum::Complex(val) => CalcNum::Complex(val.sinh()),
+            CalcNum::Symbolic(_) => self.symbolic_func("sinh"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => self.untagged().sinh(),
+        }
+    }
+    fn cosh(&self) -> CalcNum {
+        match self {
+            CalcNum::Number(val) => CalcNum::Number(val.cosh()),
+            CalcNum::Complex(val) => CalcNum::Complex(val.cosh()),
+            CalcNum::Symbolic(_) => self.symbolic_func("cosh"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => self.untagged().cosh(),
+        }
+    }
+    fn tanh(&self) -> CalcNum {
+        match self {
+            CalcNum::Number(val) => CalcNum::Number(val.tanh()),
+            CalcNum::Complex(val) => CalcNum::Complex(val.tanh()),
+            CalcNum::Symbolic(_) => self.symbolic_func("tanh"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => self.untagged().tanh(),
+        }
+    }
+    fn asinh(&self) -> CalcNum {
+        match self {
+            CalcNum::Number(val) => CalcNum::Number(val.asinh()),
+            CalcNum::Complex(val) => CalcNum::Complex(val.asinh()),
+            CalcNum::Symbolic(_) => self.symbolic_func("asinh"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().asinh()
             }
         }
     }
-    fn to_rectangular(&self, degmode: &DegMode) -> Result<CalcNum, Box<dyn std::error::Error>> {
+    fn acosh(&self) -> CalcNum {
+        // 1未満の実数は複素数に拡張
         match self {
-            CalcNum::Complex(polardata) => {
-                let theta = match degmode {
-                    DegMode::Deg => polardata.im.to_radians(),
-                    DegMode::Rad => polardata.im,
-                };
-                Ok(CalcNum::Complex(Complex::from_polar(polardata.re, theta)))
+            CalcNum::Number(val) if *val < 1.0 => CalcNum::Complex(Complex::new(*val, 0.0).acosh()),
+            CalcNum::Number(val) => CalcNum::Number(val.acosh()),
+            CalcNum::Complex(val) => CalcNum::Complex(val.acosh()),
+            CalcNum::Symbolic(_) => self.symbolic_func("acosh"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().acosh()
+            }
+        }
+    }
+    fn atanh(&self) -> CalcNum {
+        // 絶対値が1より大きい実数は複素数に拡張
+        match self {
+            CalcNum::Number(val) if val.abs() > 1.0 => {
+                CalcNum::Complex(Complex::new(*val, 0.0).atanh())
+            }
+            CalcNum::Number(val) => CalcNum::Number(val.atanh()),
+            CalcNum::Complex(val) => CalcNum::Complex(val.atanh()),
+            CalcNum::Symbolic(_) => self.symbolic_func("atanh"),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().atanh()
             }
-            CalcNum::Number(_) => Err("Invalid Type".into()),
+        }
+    }
+
+    fn to_polar(
+        &self,
+        degmode: &DegMode,
+    ) -> Result<(CalcNum, CalcNum), Box<dyn std::error::Error>> {
+        // 極座標変換 (r, θ)
+        let (r, theta) = self.to_complex()?.to_polar();
+        Ok((
+            CalcNum::Number(r),
+            CalcNum::Angle(degmode.units_of(theta), *degmode),
+        ))
+    }
+    fn to_rectangular(
+        &self,
+        r: &CalcNum,
+        degmode: &DegMode,
+    ) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        // selfを角度θとして r∠θ を複素数にする
+        let theta = self.radians(degmode)?;
+        Ok(CalcNum::Complex(Complex::from_polar(
+            r.get_realnumber()?,
+            theta,
+        )))
+    }
+
+    fn re(&self) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        Ok(CalcNum::Number(self.to_complex()?.re))
+    }
+    fn im(&self) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        Ok(CalcNum::Number(self.to_complex()?.im))
+    }
+    fn conj(&self) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        match self {
+            CalcNum::Complex(val) => Ok(CalcNum::Complex(val.conj())),
+            CalcNum::Number(_) | CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                Ok(self.untagged())
+            }
+            CalcNum::Symbolic(_) => Err("Symbolic value is not supported".into()),
+        }
+    }
+    fn arg(&self, degmode: &DegMode) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        Ok(self.to_polar(degmode)?.1)
+    }
+    fn cis(&self, degmode: &DegMode) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        // cos θ + i sin θ
+        match self {
+            CalcNum::Complex(theta) => Ok(CalcNum::Complex((Complex::<f64>::i() * theta).exp())),
+            _ => self.to_rectangular(&CalcNum::Number(1.0), degmode),
         }
     }
 
     fn to_deg(&self) -> Result<CalcNum, Box<dyn std::error::Error>> {
         match self {
-            CalcNum::Number(val) => Ok(CalcNum::Number(val.to_degrees())),
-            CalcNum::Complex(_val) => Err("Complex number is not supported".into()),
+            CalcNum::Number(val) => Ok(CalcNum::Angle(val.to_degrees(), DegMode::Deg)),
+            CalcNum::Angle(val, unit) => Ok(CalcNum::Angle(
+                DegMode::Deg.convert(*val, unit),
+                DegMode::Deg,
+            )),
+            _ => Err("Only real number is supported".into()),
         }
     }
 
     fn to_rad(&self) -> Result<CalcNum, Box<dyn std::error::Error>> {
         match self {
-            CalcNum::Number(val) => Ok(CalcNum::Number(val.to_radians())),
-            CalcNum::Complex(_val) => Err("Complex number is not supported".into()),
+            CalcNum::Number(val) => Ok(CalcNum::Angle(val.to_radians(), DegMode::Rad)),
+            CalcNum::Angle(val, unit) => Ok(CalcNum::Angle(
+                DegMode::Rad.convert(*val, unit),
+                DegMode::Rad,
+            )),
+            _ => Err("Only real number is supported".into()),
         }
     }
 
     fn factorial(&self) -> Result<CalcNum, Box<dyn std::error::Error>> {
         // 階乗計算
-        if !self.is_integer() {
-            return Err("Factorial is only supported for integer".into());
+        // 整数以外(実数・複素数)は Γ(x+1) で計算する
+        match self {
+            CalcNum::Number(n) if self.is_integer() => {
+                if *n < 0.0 {
+                    return Err("Factorial is undefined for negative integer".into());
+                }
+                match safe_product(1..=*n as u64) {
+                    Ok(result) => Ok(CalcNum::Number(result as f64)),
+                    Err(_) => Ok(CalcNum::Number(special::gamma(n + 1.0))),
+                }
+            }
+            CalcNum::Symbolic(_) => Err("Symbolic value is not supported".into()),
+            _ => (self.clone() + CalcNum::Number(1.0)).gamma(),
         }
+    }
+
+    fn gamma(&self) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        // ガンマ関数
         match self {
-            CalcNum::Number(n) => {
-                let result = safe_product(1..=*n as u64)? as f64;
-                Ok(CalcNum::Number(result))
+            CalcNum::Number(val) if *val <= 0.0 && val.fract() == 0.0 => {
+                Err("Gamma is undefined for non-positive integer".into())
+            }
+            CalcNum::Number(val) => Ok(CalcNum::Number(special::gamma(*val))),
+            CalcNum::Complex(val) => Ok(CalcNum::Complex(special::gamma_complex(*val))),
+            CalcNum::Symbolic(_) => Err("Symbolic value is not supported".into()),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().gamma()
             }
-            CalcNum::Complex(_val) => Err("Complex number is not supported".into()),
+        }
+    }
+
+    fn ln_gamma(&self) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        // ガンマ関数の対数。Γ(x) < 0 の実数 (x < 0 で floor(x) が奇数) は複素数(虚部π)になる
+        // Γ を経由するとあふれるので、符号は x から決める
+        match self {
+            CalcNum::Number(val) if *val <= 0.0 && val.fract() == 0.0 => {
+                Err("Gamma is undefined for non-positive integer".into())
+            }
+            CalcNum::Number(val) if *val < 0.0 && val.floor().rem_euclid(2.0) == 1.0 => Ok(
+                CalcNum::Complex(Complex::new(special::ln_gamma(*val), f64::consts::PI)),
+            ),
+            CalcNum::Number(val) => Ok(CalcNum::Number(special::ln_gamma(*val))),
+            CalcNum::Complex(val) => Ok(CalcNum::Complex(special::ln_gamma_complex(*val))),
+            CalcNum::Symbolic(_) => Err("Symbolic value is not supported".into()),
+            CalcNum::Angle(..) | CalcNum::Money(..) | CalcNum::Integer(_) => {
+                self.untagged().ln_gamma()
+            }
+        }
+    }
+
+    fn special_real(
+        &self,
+        func: fn(f64) -> Option<f64>,
+    ) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        // 実数のみ対応する特殊関数
+        match func(self.get_realnumber()?) {
+            Some(result) => Ok(CalcNum::Number(result)),
+            None => Err("Domain Error".into()),
+        }
+    }
+
+    fn bessel(&self, n: &Self, second_kind: bool) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        // 整数次のベッセル関数
+        if !n.is_integer() {
+            return Err("Bessel order is only supported for integer".into());
+        }
+        let x = self.get_realnumber()?;
+        let n = n.get_realnumber()?;
+        if n.abs() > special::BESSEL_MAX_ORDER as f64 {
+            return Err(
+                format!("Bessel order must be within ±{}", special::BESSEL_MAX_ORDER).into(),
+            );
+        }
+        let result = if second_kind {
+            special::bessel_y(n as i32, x)
+        } else {
+            special::bessel_j(n as i32, x)
+        };
+        Ok(CalcNum::Number(result.ok_or("Domain Error")?))
+    }
+
+    fn beta(&self, b: &Self) -> Result<CalcNum, Box<dyn std::error::Error>> {
+        // ベータ関数 B(a, b) = Γ(a)Γ(b) / Γ(a+b)
+        match (self, b) {
+            (CalcNum::Number(a), CalcNum::Number(b)) if *a > 0.0 && *b > 0.0 => {
+                Ok(CalcNum::Number(special::ln_beta(*a, *b).exp()))
+            }
+            _ => Ok(self.gamma()? * b.gamma()? / (self.clone() + b.clone()).gamma()?),
         }
     }
 
@@ -609,7 +1703,7 @@ impl CalcNum {
                 let result = safe_product(start as u64..=*n as u64)? as f64;
                 Ok(CalcNum::Number(result))
             }
-            CalcNum::Complex(_val) => Err("Complex number is not supported".into()),
+            _ => Err("Only real number is supported".into()),
         }
     }
 
@@ -624,7 +1718,18 @@ impl CalcNum {
                 let result2 = safe_product(1..=r.get_realnumber()? as u64)? as f64;
                 Ok(CalcNum::Number(result1 / result2))
             }
-            CalcNum::Complex(_n) => Err("Complex number is not supported".into()),
+            _ => Err("Only real number is supported".into()),
+        }
+    }
+
+    fn to_complex(&self) -> Result<Complex<f64>, String> {
+        match self {
+            CalcNum::Number(val) | CalcNum::Angle(val, _) | CalcNum::Money(val, _) => {
+                Ok(Complex::new(*val, 0.0))
+            }
+            CalcNum::Integer(val) => Ok(Complex::new(*val as f64, 0.0)),
+            CalcNum::Complex(val) => Ok(*val),
+            CalcNum::Symbolic(_) => Err("Symbolic value is not supported".to_string()),
         }
     }
 
@@ -632,6 +1737,10 @@ impl CalcNum {
         match self {
             CalcNum::Number(val) => CalcNum::Number(val.abs()),
             CalcNum::Complex(val) => CalcNum::Number(val.norm()),
+            CalcNum::Symbolic(_) => self.symbolic_func("abs"),
+            CalcNum::Angle(val, unit) => CalcNum::Angle(val.abs(), *unit),
+            CalcNum::Money(val, code) => CalcNum::Money(val.abs(), code),
+            CalcNum::Integer(val) => CalcNum::Integer(val.abs()),
         }
     }
 }
@@ -645,12 +1754,13 @@ pub fn manage_stack(
     degmode: &mut DegMode,
     memory_map: &mut BTreeMap<String, CalcNum>,
     memo_mode: &mut Option<Memorize>,
+    registers: &mut Registers,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // 式を分割するクロージャ
     let separate_exp = |x: &str| match x.chars().last() {
         Some(c) => match c {
             '+' | '-' | '*' | '/' | '^' => {
-                if x.len() > 1 {
+                if x.len() > 1 && !is_stat_command(x) {
                     let (head, tail) = x.split_at(x.len() - 1);
                     vec![head.to_string(), tail.to_string()]
                 } else {
@@ -663,22 +1773,30 @@ pub fn manage_stack(
     };
 
     // 入力された式を空白で分割し、それぞれの要素をparse_exp関数で処理
-    let items = expression
-        .split_whitespace()
-        .flat_map(separate_exp)
+    let items = tokenize(expression)
+        .into_iter()
+        .flat_map(|token| match token.starts_with(['[', '"', '\'']) {
+            true => vec![token],
+            false => separate_exp(&token),
+        })
         .map(|arg: String| parse_exp(&arg, memo_mode))
         .collect::<Result<Vec<_>, _>>()?;
 
     // --各機能を担うクロージャ群--
     // 記憶関連の処理
-    let mut manage_memorize = |memo: Memorize, calstack: &mut VecDeque<CalcNum>| {
+    let manage_memorize = |memo: Memorize,
+                           calstack: &mut VecDeque<CalcNum>,
+                           memory_map: &mut BTreeMap<String, CalcNum>,
+                           registers: &mut Registers|
+     -> Result<(), String> {
+        let mode = registers.payment_mode;
         match memo {
             Memorize::Recall(key) => {
                 if let Some(inkey) = key {
                     if let Some(val) = memory_map.get(&inkey) {
                         calstack.push_back(val.clone());
                     } else {
-                        return Err("No Key");
+                        return Err("No Key".into());
                     }
                 }
             }
@@ -698,49 +1816,50 @@ pub fn manage_stack(
                         memory_map.insert(inkey, val.clone());
                         calstack.push_back(val);
                     } else {
-                        return Err("Stack is Empty");
+                        return Err("Stack is Empty".into());
                     }
                 }
             }
             Memorize::Tvm(key) => {
                 if let Some(inkey) = key {
                     match inkey.as_str() {
-                        "n" => match TvmItem::n_value(memory_map) {
+                        "n" => match TvmItem::n_value(memory_map, mode) {
                             Some(value) => {
                                 calstack.push_back(CalcNum::Number(value));
                                 memory_map.insert("n".to_string(), CalcNum::Number(value));
                             }
-                            None => return Err("n_value Error"),
+                            None => return Err("n_value Error".into()),
                         },
-                        "iyr" => match TvmItem::i_year_rate(memory_map) {
-                            Some(value) => {
-                                calstack.push_back(CalcNum::Number(value));
-                                memory_map.insert("iyr".to_string(), CalcNum::Number(value));
+                        "iyr" => match TvmItem::i_year_rate(memory_map, mode) {
+                            Ok(report) => {
+                                calstack.push_back(CalcNum::Number(report.rate));
+                                memory_map.insert("iyr".to_string(), CalcNum::Number(report.rate));
+                                registers.notice = report.summary();
                             }
-                            None => return Err("i_year Error"),
+                            Err(e) => return Err(e),
                         },
-                        "pv" => match TvmItem::present_value(memory_map) {
+                        "pv" => match TvmItem::present_value(memory_map, mode) {
                             Some(value) => {
                                 calstack.push_back(CalcNum::Number(value));
                                 memory_map.insert("pv".to_string(), CalcNum::Number(value));
                             }
-                            None => return Err("pv Error"),
+                            None => return Err("pv Error".into()),
                         },
-                        "pmt" => match TvmItem::payment(memory_map) {
+                        "pmt" => match TvmItem::payment(memory_map, mode) {
                             Some(value) => {
                                 calstack.push_back(CalcNum::Number(value));
                                 memory_map.insert("pmt".to_string(), CalcNum::Number(value));
                             }
-                            None => return Err("pmt Error"),
+                            None => return Err("pmt Error".into()),
                         },
-                        "fv" => match TvmItem::future_value(memory_map) {
+                        "fv" => match TvmItem::future_value(memory_map, mode) {
                             Some(value) => {
                                 calstack.push_back(CalcNum::Number(value));
                                 memory_map.insert("fv".to_string(), CalcNum::Number(value));
                             }
-                            None => return Err("fv Error"),
+                            None => return Err("fv Error".into()),
                         },
-                        _ => return Err("Tvm Error"),
+                        _ => return Err("Tvm Error".into()),
                     }
                 }
             }
@@ -749,35 +1868,94 @@ pub fn manage_stack(
     };
 
     // 二項演算の処理
-    let manage_binomial = |b_func: BinomialFunc, calstack: &mut VecDeque<CalcNum>| {
-        let (exex, ex) = get_two_item(calstack)?;
-        let result = match b_func {
-            BinomialFunc::Add => exex + ex,
-            BinomialFunc::Subtract => exex - ex,
-            BinomialFunc::Multiply => exex * ex,
-            BinomialFunc::Divide => exex / ex,
-            BinomialFunc::Mod => exex % ex,
-            BinomialFunc::Pow => exex.pow(&ex),
-            BinomialFunc::NPr => match exex.permutation(&ex) {
-                Ok(result) => result,
-                Err(e) => {
-                    calstack.push_back(exex);
-                    calstack.push_back(ex);
-                    return Err(e);
+    let manage_binomial =
+        |b_func: BinomialFunc, calstack: &mut VecDeque<CalcNum>, modulus: Option<u64>| {
+            let (exex, ex) = get_two_item(calstack)?;
+            // 剰余演算モードでは + - * / ^ を Z/nZ で計算する
+            if let Some(m) = modulus
+                && let Some(result) = modular_binomial(&b_func, &exex, &ex, m)
+            {
+                match result {
+                    Ok(value) => calstack.push_back(value),
+                    Err(e) => {
+                        calstack.push_back(exex);
+                        calstack.push_back(ex);
+                        return Err(e.into());
+                    }
                 }
-            },
-            BinomialFunc::NCr => match exex.combination(&ex) {
-                Ok(result) => result,
-                Err(e) => {
-                    calstack.push_back(exex);
-                    calstack.push_back(ex);
-                    return Err(e);
+                return Ok(());
+            }
+            if let Err(e) = CalcNum::currency_check(&b_func, &exex, &ex) {
+                calstack.push_back(exex);
+                calstack.push_back(ex);
+                return Err(e.into());
+            }
+            // 記号式を含む場合は式の木を作る
+            if exex.is_symbolic() || ex.is_symbolic() {
+                let result = match (&b_func, exex.to_symbolic(), ex.to_symbolic()) {
+                    (_, Err(e), _) | (_, _, Err(e)) => Err(e),
+                    (BinomialFunc::Add, Ok(a), Ok(b)) => Ok(a + b),
+                    (BinomialFunc::Subtract, Ok(a), Ok(b)) => Ok(a - b),
+                    (BinomialFunc::Multiply, Ok(a), Ok(b)) => Ok(a * b),
+                    (BinomialFunc::Divide, Ok(a), Ok(b)) => Ok(a / b),
+                    (BinomialFunc::Pow, Ok(a), Ok(b)) => Ok(a.pow(b)),
+                    _ => Err("Symbolic value is not supported".to_string()),
+                };
+                match result {
+                    Ok(sym) => calstack.push_back(CalcNum::Symbolic(sym)),
+                    Err(e) => {
+                        calstack.push_back(exex);
+                        calstack.push_back(ex);
+                        return Err(e.into());
+                    }
                 }
-            },
+                return Ok(());
+            }
+            let result = match b_func {
+                BinomialFunc::Add => exex + ex,
+                BinomialFunc::Subtract => exex - ex,
+                BinomialFunc::Multiply => exex * ex,
+                BinomialFunc::Divide => exex / ex,
+                BinomialFunc::Mod => exex % ex,
+                BinomialFunc::Pow => exex.pow(&ex),
+                BinomialFunc::NPr => match exex.permutation(&ex) {
+                    Ok(result) => result,
+                    Err(e) => {
+                        calstack.push_back(exex);
+                        calstack.push_back(ex);
+                        return Err(e);
+                    }
+                },
+                BinomialFunc::Beta => match exex.beta(&ex) {
+                    Ok(result) => result,
+                    Err(e) => {
+                        calstack.push_back(exex);
+                        calstack.push_back(ex);
+                        return Err(e);
+                    }
+                },
+                BinomialFunc::BesselJ | BinomialFunc::BesselY => {
+                    match exex.bessel(&ex, matches!(b_func, BinomialFunc::BesselY)) {
+                        Ok(result) => result,
+                        Err(e) => {
+                            calstack.push_back(exex);
+                            calstack.push_back(ex);
+                            return Err(e);
+                        }
+                    }
+                }
+                BinomialFunc::NCr => match exex.combination(&ex) {
+                    Ok(result) => result,
+                    Err(e) => {
+                        calstack.push_back(exex);
+                        calstack.push_back(ex);
+                        return Err(e);
+                    }
+                },
+            };
+            calstack.push_back(result);
+            Ok(())
         };
-        calstack.push_back(result);
-        Ok(())
-    };
 
     // 単項演算の処理
     let manage_monomial = |m_func, calstack: &mut VecDeque<CalcNum>, degmode: &mut DegMode| {
@@ -792,6 +1970,12 @@ pub fn manage_stack(
             MonomialFunc::ASin => ex.asin(degmode),
             MonomialFunc::ACos => ex.acos(degmode),
             MonomialFunc::ATan => ex.atan(degmode),
+            MonomialFunc::Sinh => ex.sinh(),
+            MonomialFunc::Cosh => ex.cosh(),
+            MonomialFunc::Tanh => ex.tanh(),
+            MonomialFunc::ASinh => ex.asinh(),
+            MonomialFunc::ACosh => ex.acosh(),
+            MonomialFunc::ATanh => ex.atanh(),
             MonomialFunc::ToDeg => match ex.to_deg() {
                 Ok(deg) => deg,
                 Err(e) => {
@@ -813,82 +1997,730 @@ pub fn manage_stack(
                     return Err(e);
                 }
             },
-            MonomialFunc::Abs => ex.abs(),
-            MonomialFunc::ToPolar => match ex.to_polar(degmode) {
-                Ok(polardata) => polardata,
+            MonomialFunc::Gamma => match ex.gamma() {
+                Ok(result) => result,
                 Err(e) => {
                     calstack.push_back(ex);
                     return Err(e);
                 }
             },
-            MonomialFunc::ToRec => match ex.to_rectangular(degmode) {
+            MonomialFunc::LnGamma => match ex.ln_gamma() {
                 Ok(result) => result,
                 Err(e) => {
                     calstack.push_back(ex);
                     return Err(e);
                 }
             },
+            MonomialFunc::Erf
+            | MonomialFunc::Erfc
+            | MonomialFunc::EllipK
+            | MonomialFunc::EllipE
+            | MonomialFunc::Zeta => {
+                let func: fn(f64) -> Option<f64> = match m_func {
+                    MonomialFunc::Erf => |x| Some(special::erf(x)),
+                    MonomialFunc::Erfc => |x| Some(special::erfc(x)),
+                    MonomialFunc::EllipK => special::elliptic_k,
+                    MonomialFunc::EllipE => special::elliptic_e,
+                    _ => special::zeta,
+                };
+                match ex.special_real(func) {
+                    Ok(result) => result,
+                    Err(e) => {
+                        calstack.push_back(ex);
+                        return Err(e);
+                    }
+                }
+            }
+            MonomialFunc::Abs => ex.abs(),
+            // 極座標変換は r と θ の2つを置く
+            MonomialFunc::ToPolar => match ex.to_polar(degmode) {
+                Ok((r, theta)) => {
+                    calstack.push_back(r);
+                    theta
+                }
+                Err(e) => {
+                    calstack.push_back(ex);
+                    return Err(e);
+                }
+            },
+            // r θ torec
+            MonomialFunc::ToRec => {
+                let result = match calstack.back() {
+                    Some(r) => ex.to_rectangular(r, degmode),
+                    None => Err("Stack is too short".into()),
+                };
+                match result {
+                    Ok(result) => {
+                        calstack.pop_back();
+                        result
+                    }
+                    Err(e) => {
+                        calstack.push_back(ex);
+                        return Err(e);
+                    }
+                }
+            }
+            MonomialFunc::Re
+            | MonomialFunc::Im
+            | MonomialFunc::Conj
+            | MonomialFunc::Arg
+            | MonomialFunc::Cis => {
+                let result = match m_func {
+                    MonomialFunc::Re => ex.re(),
+                    MonomialFunc::Im => ex.im(),
+                    MonomialFunc::Conj => ex.conj(),
+                    MonomialFunc::Arg => ex.arg(degmode),
+                    _ => ex.cis(degmode),
+                };
+                match result {
+                    Ok(result) => result,
+                    Err(e) => {
+                        calstack.push_back(ex);
+                        return Err(e);
+                    }
+                }
+            }
         };
         calstack.push_back(result);
         Ok(())
     };
 
     // スタック操作・演算の処理
-    let manage_operate_stack =
-        |operate, calstack: &mut VecDeque<CalcNum>, degmode: &mut DegMode| {
-            match operate {
-                OperateStack::Swap => {
-                    if calstack.len() < 2 {
-                        return Err("Stack is too short");
-                    } else {
-                        let last = calstack.len() - 1;
-                        calstack.swap(last, last - 1);
-                    }
+    let manage_operate_stack = |operate,
+                                calstack: &mut VecDeque<CalcNum>,
+                                degmode: &mut DegMode,
+                                registers: &mut Registers| {
+        match operate {
+            OperateStack::Swap => {
+                if calstack.len() < 2 {
+                    return Err("Stack is too short");
+                } else {
+                    let last = calstack.len() - 1;
+                    calstack.swap(last, last - 1);
                 }
-                OperateStack::Clear => calstack.clear(),
-                OperateStack::Delete => {
-                    if calstack.is_empty() {
-                        return Err("Stack is Empty");
-                    } else {
-                        calstack.pop_back();
+            }
+            OperateStack::Clear => calstack.clear(),
+            OperateStack::Delete => {
+                if calstack.is_empty() {
+                    return Err("Stack is Empty");
+                } else {
+                    calstack.pop_back();
+                }
+            }
+            OperateStack::RollUp => {
+                if calstack.len() < 2 {
+                    return Err("Stack is too short");
+                } else {
+                    let last = calstack.pop_back().unwrap();
+                    calstack.push_front(last);
+                }
+            }
+            OperateStack::RollDown => {
+                if calstack.len() < 2 {
+                    return Err("Stack is too short");
+                } else {
+                    let first = calstack.pop_front().unwrap();
+                    calstack.push_back(first);
+                }
+            }
+            OperateStack::Sum => {
+                if calstack.iter().all(CalcNum::is_realnumber) {
+                    let sum_result = calstack.iter().map(|x| x.get_realnumber().unwrap()).sum();
+                    calstack.clear();
+                    calstack.push_back(CalcNum::Number(sum_result));
+                } else {
+                    return Err("Invalid Data");
+                }
+            }
+            OperateStack::Deg => *degmode = DegMode::Deg,
+            OperateStack::Rad => *degmode = DegMode::Rad,
+            OperateStack::Grad => *degmode = DegMode::Grad,
+            OperateStack::Turn => *degmode = DegMode::Turn,
+            OperateStack::SetMod => {
+                let m = get_integer_items(calstack, 1).map_err(|_| "Invalid Modulus")?[0];
+                match m {
+                    0 => registers.modulus = None,
+                    2.. => registers.modulus = Some(m as u64),
+                    _ => {
+                        calstack.push_back(CalcNum::Number(m as f64));
+                        return Err("Invalid Modulus");
                     }
                 }
-                OperateStack::RollUp => {
-                    if calstack.len() < 2 {
-                        return Err("Stack is too short");
+            }
+            OperateStack::ClearMod => registers.modulus = None,
+            OperateStack::Polar => registers.complex_form = ComplexForm::Polar,
+            OperateStack::Begin => registers.payment_mode = finance::PaymentMode::Begin,
+            OperateStack::End => registers.payment_mode = finance::PaymentMode::End,
+            OperateStack::Rect => registers.complex_form = ComplexForm::Rect,
+        }
+        Ok(())
+    };
+
+    // 統計処理
+    let manage_statistics =
+        |stat: Statistics, calstack: &mut VecDeque<CalcNum>, registers: &mut Registers| {
+            match stat {
+                Statistics::Add | Statistics::Remove => {
+                    let x = get_one_item(calstack)?;
+                    let Ok(xval) = x.get_realnumber() else {
+                        calstack.push_back(x);
+                        return Err("Invalid Data".into());
+                    };
+                    let count = match stat {
+                        Statistics::Add => registers.stat.push(xval, None),
+                        _ => match registers.stat.remove(xval, None) {
+                            Some(count) => count,
+                            None => {
+                                calstack.push_back(x);
+                                return Err("No such data".into());
+                            }
+                        },
+                    };
+                    calstack.push_back(CalcNum::Number(count as f64));
+                }
+                Statistics::Add2 | Statistics::Remove2 => {
+                    let (x, y) = get_two_item(calstack)?;
+                    let (Ok(xval), Ok(yval)) = (x.get_realnumber(), y.get_realnumber()) else {
+                        calstack.push_back(x);
+                        calstack.push_back(y);
+                        return Err("Invalid Data".into());
+                    };
+                    let count = match stat {
+                        Statistics::Add2 => registers.stat.push(xval, Some(yval)),
+                        _ => match registers.stat.remove(xval, Some(yval)) {
+                            Some(count) => count,
+                            None => {
+                                calstack.push_back(x);
+                                calstack.push_back(y);
+                                return Err("No such data".into());
+                            }
+                        },
+                    };
+                    calstack.push_back(CalcNum::Number(count as f64));
+                }
+                Statistics::Clear => registers.stat.clear(),
+                Statistics::Fit(model) => {
+                    // 二変数の統計データがなければスタックの x1 y1 x2 y2 .. を使う
+                    let (xs, ys) = if registers.stat.is_empty() {
+                        let data = calstack
+                            .iter()
+                            .map(CalcNum::get_realnumber)
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|_| "Invalid Data")?;
+                        if data.len() % 2 != 0 {
+                            return Err("Stack must hold x y pairs".into());
+                        }
+                        data.chunks(2).map(|pair| (pair[0], pair[1])).unzip()
                     } else {
-                        let last = calstack.pop_back().unwrap();
-                        calstack.push_front(last);
+                        registers.stat.pairs().ok_or("Stat data has no y values")?
+                    };
+                    let result = match model {
+                        Some(model) => stats::fit(model, &xs, &ys),
+                        None => stats::best_fit(&xs, &ys),
+                    };
+                    let Some(fit) = result else {
+                        return Err("Regression Error".into());
+                    };
+                    if registers.stat.is_empty() {
+                        calstack.clear();
                     }
+                    calstack.push_back(CalcNum::Number(fit.a));
+                    calstack.push_back(CalcNum::Number(fit.b));
+                    registers.fit = Some(fit);
+                }
+                Statistics::Corr | Statistics::RSquared => {
+                    let fit = registers.fit.ok_or("No fit")?;
+                    let result = match stat {
+                        Statistics::Corr => fit.r,
+                        _ => fit.r_squared(),
+                    };
+                    calstack.push_back(CalcNum::Number(result));
+                }
+                Statistics::YHat | Statistics::XHat => {
+                    let fit = registers.fit.ok_or("No fit")?;
+                    let ex = get_one_item(calstack)?;
+                    let Ok(value) = ex.get_realnumber() else {
+                        calstack.push_back(ex);
+                        return Err("Invalid Data".into());
+                    };
+                    let result = match stat {
+                        Statistics::YHat => fit.predict_y(value),
+                        _ => fit.predict_x(value),
+                    };
+                    calstack.push_back(CalcNum::Number(result));
                 }
-                OperateStack::RollDown => {
-                    if calstack.len() < 2 {
-                        return Err("Stack is too short");
+                _ => {
+                    // 統計データが空の場合はスタック全体を対象とする(スタックは消さない)
+                    let data = if registers.stat.is_empty() {
+                        calstack
+                            .iter()
+                            .map(CalcNum::get_realnumber)
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|_| "Invalid Data")?
                     } else {
-                        let first = calstack.pop_front().unwrap();
-                        calstack.push_back(first);
+                        registers.stat.xs()
+                    };
+                    let result = match stat {
+                        Statistics::Count => Some(data.len() as f64),
+                        Statistics::Mean => stats::mean(&data),
+                        Statistics::SDev => stats::std_dev(&data, true),
+                        Statistics::PSDev => stats::std_dev(&data, false),
+                        Statistics::Var => stats::variance(&data, true),
+                        Statistics::PVar => stats::variance(&data, false),
+                        Statistics::Median => stats::median(&data),
+                        Statistics::Min => stats::min(&data),
+                        Statistics::Max => stats::max(&data),
+                        _ => None,
+                    };
+                    match result {
+                        Some(value) => calstack.push_back(CalcNum::Number(value)),
+                        None => return Err("Not enough data".into()),
                     }
                 }
-                OperateStack::Sum => {
-                    if calstack.iter().all(CalcNum::is_realnumber) {
-                        let sum_result = calstack.iter().map(|x| x.get_realnumber().unwrap()).sum();
-                        calstack.clear();
-                        calstack.push_back(CalcNum::Number(sum_result));
-                    } else {
-                        return Err("Invalid Data");
+            }
+            Ok::<(), Box<dyn std::error::Error>>(())
+        };
+
+    // 確率分布の処理
+    let manage_distribution = |dist: Distribution,
+                               func: DistFunc,
+                               calstack: &mut VecDeque<CalcNum>| {
+        let args = get_real_items(calstack, dist.arity())?;
+        let result = match (dist, func) {
+            (Distribution::Normal, DistFunc::Pdf) => distrib::normal_pdf(args[0], args[1], args[2]),
+            (Distribution::Normal, DistFunc::Cdf) => distrib::normal_cdf(args[0], args[1], args[2]),
+            (Distribution::Normal, DistFunc::Inv) => distrib::normal_inv(args[0], args[1], args[2]),
+            (Distribution::StudentT, DistFunc::Pdf) => distrib::t_pdf(args[0], args[1]),
+            (Distribution::StudentT, DistFunc::Cdf) => distrib::t_cdf(args[0], args[1]),
+            (Distribution::StudentT, DistFunc::Inv) => distrib::t_inv(args[0], args[1]),
+            (Distribution::ChiSquare, DistFunc::Pdf) => distrib::chi2_pdf(args[0], args[1]),
+            (Distribution::ChiSquare, DistFunc::Cdf) => distrib::chi2_cdf(args[0], args[1]),
+            (Distribution::ChiSquare, DistFunc::Inv) => distrib::chi2_inv(args[0], args[1]),
+            (Distribution::F, DistFunc::Pdf) => distrib::f_pdf(args[0], args[1], args[2]),
+            (Distribution::F, DistFunc::Cdf) => distrib::f_cdf(args[0], args[1], args[2]),
+            (Distribution::F, DistFunc::Inv) => distrib::f_inv(args[0], args[1], args[2]),
+            (Distribution::Binomial, DistFunc::Pdf) => {
+                distrib::binomial_pmf(args[0], args[1], args[2])
+            }
+            (Distribution::Binomial, DistFunc::Cdf) => {
+                distrib::binomial_cdf(args[0], args[1], args[2])
+            }
+            (Distribution::Binomial, DistFunc::Inv) => {
+                distrib::binomial_inv(args[0], args[1], args[2])
+            }
+            (Distribution::Poisson, DistFunc::Pdf) => distrib::poisson_pmf(args[0], args[1]),
+            (Distribution::Poisson, DistFunc::Cdf) => distrib::poisson_cdf(args[0], args[1]),
+            (Distribution::Poisson, DistFunc::Inv) => distrib::poisson_inv(args[0], args[1]),
+        };
+        match result {
+            Some(value) => {
+                calstack.push_back(CalcNum::Number(value));
+                Ok(())
+            }
+            None => {
+                calstack.extend(args.into_iter().map(CalcNum::Number));
+                Err("Domain Error".to_string())
+            }
+        }
+    };
+
+    // 乱数の処理
+    let manage_random = |rand: Random, calstack: &mut VecDeque<CalcNum>, rng: &mut Rng| {
+        let result = match rand {
+            Random::Rand => rng.uniform(),
+            Random::RandN => rng.normal(),
+            Random::RandInt => {
+                let args = get_real_items(calstack, 2)?;
+                if args.iter().any(|x| x.fract() != 0.0 || x.abs() > 9.0e15) {
+                    calstack.extend(args.into_iter().map(CalcNum::Number));
+                    return Err("randint is only supported for integer".to_string());
+                }
+                rng.range_int(args[0] as i64, args[1] as i64) as f64
+            }
+            Random::Seed => {
+                let seed = get_real_items(calstack, 1)?[0];
+                *rng = if seed >= 0.0 && seed.fract() == 0.0 {
+                    Rng::new(seed as u64)
+                } else {
+                    Rng::new(seed.to_bits())
+                };
+                return Ok(());
+            }
+            Random::Dice(count, sides) => rng.dice(count, sides) as f64,
+        };
+        calstack.push_back(CalcNum::Number(result));
+        Ok(())
+    };
+
+    // 整数論の処理
+    let manage_number_theory =
+        |func: NumberTheory, calstack: &mut VecDeque<CalcNum>, registers: &mut Registers| {
+            let args = get_integer_items(calstack, func.arity())?;
+            let restore = |calstack: &mut VecDeque<CalcNum>, message: &str| {
+                calstack.extend(args.iter().map(|x| exact_integer(*x)));
+                Err(message.to_string())
+            };
+            // get_integer_items で絶対値は u64 に収まっている
+            let unsigned = args
+                .iter()
+                .map(|x| x.unsigned_abs() as u64)
+                .collect::<Vec<_>>();
+            let result = match func {
+                NumberTheory::Gcd => Some(numtheory::gcd(unsigned[0], unsigned[1])),
+                NumberTheory::Lcm => numtheory::lcm(unsigned[0], unsigned[1]),
+                NumberTheory::IsPrime => {
+                    Some(u64::from(args[0] > 0 && numtheory::is_prime(unsigned[0])))
+                }
+                NumberTheory::NextPrime => numtheory::next_prime(args[0].max(0) as u64),
+                NumberTheory::Totient if args[0] < 1 => None,
+                NumberTheory::Totient => Some(numtheory::totient(unsigned[0])),
+                NumberTheory::PowMod if args[1] < 0 || args[2] < 1 => None,
+                NumberTheory::PowMod => {
+                    let base = args[0].rem_euclid(args[2]) as u64;
+                    numtheory::pow_mod(base, unsigned[1], unsigned[2])
+                }
+                NumberTheory::ModInv if args[1] < 1 => None,
+                NumberTheory::ModInv => {
+                    numtheory::mod_inverse(args[0].rem_euclid(args[1]) as u64, unsigned[1])
+                }
+                NumberTheory::Factor => {
+                    if args[0] < 2 {
+                        return restore(calstack, "Factor needs an integer ≥ 2");
+                    }
+                    let factors = numtheory::factorize(unsigned[0]);
+                    for (p, e) in &factors {
+                        for _ in 0..*e {
+                            calstack.push_back(exact_integer(*p as i128));
+                        }
                     }
+                    registers.notice = Some(format!(
+                        "{} = {}",
+                        args[0],
+                        numtheory::format_factors(&factors)
+                    ));
+                    return Ok(());
+                }
+            };
+            match result {
+                Some(value) => {
+                    calstack.push_back(exact_integer(value as i128));
+                    Ok(())
                 }
-                OperateStack::Deg => *degmode = DegMode::Deg,
-                OperateStack::Rad => *degmode = DegMode::Rad,
+                None => restore(calstack, "Domain Error"),
+            }
+        };
+
+    // 多項式の処理
+    let manage_polynomial = |func: Polynomial, calstack: &mut VecDeque<CalcNum>| {
+        // 係数の数(Polynomial::Evalの場合はその上にx)をスタックから取り出す
+        let x = match func {
+            Polynomial::Eval => Some(get_one_item(calstack)?),
+            Polynomial::Roots => None,
+        };
+        let restore = |calstack: &mut VecDeque<CalcNum>, items: Vec<CalcNum>, message: &str| {
+            calstack.extend(items);
+            if let Some(x) = &x {
+                calstack.push_back(x.clone());
+            }
+            Err(message.to_string())
+        };
+        let count = get_one_item(calstack)?;
+        if !count.is_integer()
+            || count.get_realnumber().unwrap_or(0.0) < 1.0
+            || count.get_realnumber().unwrap_or(0.0) as usize > calstack.len()
+        {
+            return restore(calstack, vec![count], "Invalid coefficient count");
+        }
+        let n = count.get_realnumber().unwrap_or(0.0) as usize;
+        let items = calstack.split_off(calstack.len() - n);
+        let coeffs = match items
+            .iter()
+            .chain(&x)
+            .map(CalcNum::to_complex)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(coeffs) => coeffs[..n].to_vec(),
+            Err(e) => {
+                let items = items.into_iter().chain([count]).collect();
+                return restore(calstack, items, &e);
             }
-            Ok(())
         };
+        let all_real = items.iter().all(CalcNum::is_realnumber);
+        match &x {
+            Some(x) => {
+                let value = poly::eval(&coeffs, x.to_complex()?);
+                calstack.push_back(match all_real && x.is_realnumber() {
+                    true => CalcNum::Number(value.re),
+                    false => CalcNum::Complex(value),
+                });
+            }
+            None => {
+                let Some(roots) = poly::roots(&coeffs) else {
+                    let items = items.into_iter().chain([count]).collect();
+                    return restore(calstack, items, "No roots");
+                };
+                for root in roots {
+                    // 実係数で虚部が十分小さい根は実数とする
+                    let is_real = all_real && root.im.abs() <= 1e-10 * root.re.abs().max(1.0);
+                    calstack.push_back(match is_real {
+                        true => CalcNum::Number(root.re),
+                        false => CalcNum::Complex(root),
+                    });
+                }
+            }
+        }
+        Ok(())
+    };
+
+    // 登録した式の処理
+    let manage_equation = |func: Equation,
+                           calstack: &mut VecDeque<CalcNum>,
+                           degmode: &DegMode,
+                           memory_map: &BTreeMap<String, CalcNum>,
+                           registers: &mut Registers| {
+        let program = registers.equation.clone().ok_or("No equation")?;
+        let f = |x: f64| {
+            eval_program(&program, &CalcNum::Number(x), degmode, memory_map)
+                .and_then(|result| result.get_realnumber())
+                .map_err(|e| e.to_string())
+        };
+        // 複素ステップ微分は複素数を代入して評価する
+        let complex_f = |z: Complex<f64>| match eval_program(
+            &program,
+            &CalcNum::Complex(z),
+            degmode,
+            memory_map,
+        ) {
+            Ok(CalcNum::Complex(result)) => Ok(result),
+            Ok(_) => Err("Equation is not complex analytic".to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let args = match func {
+            Equation::BracketSolve | Equation::Integrate | Equation::Plot => {
+                get_real_items(calstack, 2)?
+            }
+            _ => get_real_items(calstack, 1)?,
+        };
+        let result = match func {
+            Equation::BracketSolve => numeric::brent(f, args[0], args[1]).map(|report| {
+                registers.notice = Some(report.summary());
+                vec![report.root]
+            }),
+            // HP-15Cと同様に誤差を下、積分値を上に置く
+            Equation::Integrate => numeric::integrate(f, args[0], args[1], 1e-10).map(|report| {
+                registers.notice = Some(report.summary());
+                vec![report.error, report.value]
+            }),
+            Equation::Derivative | Equation::Derivative2 => {
+                let order = if let Equation::Derivative2 = func {
+                    2
+                } else {
+                    1
+                };
+                numeric::derivative(f, args[0], order).map(|report| {
+                    registers.notice = Some(report.summary());
+                    vec![report.value]
+                })
+            }
+            // 描画は画面側で行う
+            Equation::Plot if args[0] == args[1] => Err("Empty plot range".to_string()),
+            Equation::Plot => {
+                registers.plot = Some((args[0], args[1]));
+                Ok(vec![])
+            }
+            Equation::ComplexStep => match degmode {
+                DegMode::Deg | DegMode::Grad | DegMode::Turn => {
+                    Err("Complex step requires Rad mode".to_string())
+                }
+                DegMode::Rad => numeric::complex_step(complex_f, args[0]).map(|report| {
+                    registers.notice = Some(report.summary());
+                    vec![report.value]
+                }),
+            },
+            _ => numeric::solve(f, args[0]).map(|report| {
+                registers.notice = Some(report.summary());
+                vec![report.root]
+            }),
+        };
+        match result {
+            Ok(values) => {
+                calstack.extend(values.into_iter().map(CalcNum::Number));
+                Ok(())
+            }
+            Err(e) => {
+                calstack.extend(args.into_iter().map(CalcNum::Number));
+                Err(e)
+            }
+        }
+    };
+
+    // 記号式の処理
+    let manage_algebra = |func: Algebra,
+                          calstack: &mut VecDeque<CalcNum>,
+                          memory_map: &BTreeMap<String, CalcNum>| {
+        let ex = get_one_item(calstack)?;
+        let CalcNum::Symbolic(sym) = &ex else {
+            calstack.push_back(ex);
+            return Err("Not a symbolic value".to_string());
+        };
+        let result = match func {
+            Algebra::Simplify => sym.simplify(),
+            Algebra::Expand => sym.expand(),
+            Algebra::Derivative => sym.derivative("x").simplify(),
+            // メモの数値・記号式を代入する
+            _ => sym.subst(&|name| memory_map.get(name).and_then(|v| v.to_symbolic().ok())),
+        };
+        // 変数が残らなければ数値にする (pi, e も評価する)
+        calstack.push_back(match (&func, result.eval(&|_| None)) {
+            (Algebra::Subst, Ok(val)) => CalcNum::Number(val),
+            _ => match result {
+                Sym::Num(val) => CalcNum::Number(val),
+                sym => CalcNum::Symbolic(sym),
+            },
+        });
+        Ok(())
+    };
+
+    // 通貨換算の処理
+    let manage_currency =
+        |func: Currency, calstack: &mut VecDeque<CalcNum>, registers: &mut Registers| {
+            let Currency::Convert(to) = func;
+            let ex = get_one_item(calstack)?;
+            let CalcNum::Money(amount, from) = ex else {
+                calstack.push_back(ex);
+                return Err("Not a currency amount".into());
+            };
+            match registers.rates.convert(amount, from, to) {
+                Ok((result, date)) => {
+                    let rate = result / amount;
+                    registers.notice = Some(format!("1 {from} = {rate} {to} ({date})"));
+                    calstack.push_back(CalcNum::Money(result, to));
+                    Ok(())
+                }
+                Err(e) => {
+                    calstack.push_back(ex);
+                    Err(e)
+                }
+            }
+        };
+
+    // キャッシュフローの処理
+    let manage_cashflow =
+        |func: CashFlow, calstack: &mut VecDeque<CalcNum>, registers: &mut Registers| {
+            let cashflow = &mut registers.cashflow;
+            // 引数の数
+            let arity = match func {
+                CashFlow::Initial
+                | CashFlow::Flow
+                | CashFlow::Count
+                | CashFlow::Npv
+                | CashFlow::DiscountedPayback => 1,
+                CashFlow::Mirr => 2,
+                _ => 0,
+            };
+            if calstack.len() < arity {
+                return Err("Stack is too short".to_string());
+            }
+            let args = calstack.split_off(calstack.len() - arity);
+            let values = args
+                .iter()
+                .map(|x| x.get_realnumber().map_err(|e| e.to_string()))
+                .collect::<Result<Vec<_>, _>>();
+            let result = values.and_then(|values| match func {
+                CashFlow::Initial => {
+                    cashflow.set_initial(values[0]);
+                    Ok(None)
+                }
+                CashFlow::Flow => cashflow.push(values[0]).map(|_| None),
+                CashFlow::Count => cashflow
+                    .set_count(cashflow.flows.len().saturating_sub(1), values[0])
+                    .map(|_| None),
+                CashFlow::Npv => cashflow.npv(values[0]).map(Some),
+                CashFlow::Irr => cashflow.irr().map(|report| {
+                    registers.notice = report.summary();
+                    Some(report.rate)
+                }),
+                CashFlow::Mirr => cashflow.mirr(values[0], values[1]).map(Some),
+                CashFlow::Payback => cashflow.payback(0.0).map(Some),
+                CashFlow::DiscountedPayback => cashflow.payback(values[0]).map(Some),
+                CashFlow::Clear => {
+                    *cashflow = cashflow::CashFlows::default();
+                    Ok(None)
+                }
+            });
+            match result {
+                Ok(value) => {
+                    calstack.extend(value.map(CalcNum::Number));
+                    Ok(())
+                }
+                Err(e) => {
+                    calstack.extend(args);
+                    Err(e)
+                }
+            }
+        };
+
+    // 償還表の処理
+    let manage_loan = |func: Loan,
+                       calstack: &mut VecDeque<CalcNum>,
+                       memory_map: &BTreeMap<String, CalcNum>,
+                       registers: &mut Registers| {
+        let (start, end) = get_two_item(calstack)?;
+        if !start.is_integer() || !end.is_integer() {
+            calstack.push_back(start);
+            calstack.push_back(end);
+            return Err("Period range must be integers".to_string());
+        }
+        let rows = match func {
+            Loan::Amortize => TvmItem::schedule(memory_map, registers.payment_mode)
+                .map(|rows| (rows, "Amortization (level payment)", None)),
+            Loan::EqualPrincipal => {
+                TvmItem::equal_principal_schedule(memory_map, registers.payment_mode)
+                    .map(|(rows, level)| (rows, "Amortization (equal principal)", Some(level)))
+            }
+        };
+        let schedule = rows.map(|(rows, title, level)| {
+            let schedule = amort::Schedule {
+                title: title.to_string(),
+                rows,
+                start: start.get_realnumber().unwrap_or(0.0).max(0.0) as usize,
+                end: end.get_realnumber().unwrap_or(0.0).max(0.0) as usize,
+            };
+            (schedule, level)
+        });
+        match schedule.and_then(|(schedule, level)| Ok((schedule.totals()?, schedule, level))) {
+            Ok(((interest, principal, balance), schedule, level)) => {
+                // 元金均等は初回支払額と利息合計を元利均等と並べて表示する
+                if let (Some(level), Some(first)) = (level, schedule.rows.first()) {
+                    registers.notice = Some(format!(
+                        "1st payment {:.2} | total interest {:.2} (level payment {level:.2})",
+                        first.payment,
+                        amort::total_interest(&schedule.rows)
+                    ));
+                }
+                calstack.push_back(CalcNum::Number(interest));
+                calstack.push_back(CalcNum::Number(principal));
+                calstack.push_back(CalcNum::Number(balance));
+                registers.schedule = Some(schedule);
+                Ok(())
+            }
+            Err(e) => {
+                calstack.push_back(start);
+                calstack.push_back(end);
+                Err(e)
+            }
+        }
+    };
 
     // 定数の処理
-    let manage_constant = |consts, calstack: &mut VecDeque<CalcNum>| {
+    let manage_constant = |consts, calstack: &mut VecDeque<CalcNum>, registers: &mut Registers| {
         let result = match consts {
             Constant::Pi => f64::consts::PI,
             Constant::E => f64::consts::E,
+            Constant::Physical(constant) => {
+                registers.notice = Some(constant.summary());
+                constant.value
+            }
         };
         calstack.push_back(CalcNum::Number(result));
     };
@@ -904,12 +2736,35 @@ pub fn manage_stack(
         for item in items {
             // 式の要素に応じて処理を分岐
             match item {
-                Expr::Memo(mem) => manage_memorize(mem, calstack)?,
+                Expr::Memo(mem) => manage_memorize(mem, calstack, memory_map, registers)?,
                 Expr::Numbers(data) => calstack.push_back(data),
-                Expr::Binomial(b_func) => manage_binomial(b_func, calstack)?,
+                Expr::Binomial(b_func) => manage_binomial(b_func, calstack, registers.modulus)?,
                 Expr::Monomial(m_func) => manage_monomial(m_func, calstack, degmode)?,
-                Expr::Opstack(operate) => manage_operate_stack(operate, calstack, degmode)?,
-                Expr::Const(consts) => manage_constant(consts, calstack),
+                Expr::Opstack(operate) => {
+                    manage_operate_stack(operate, calstack, degmode, registers)?
+                }
+                Expr::Const(consts) => manage_constant(consts, calstack, registers),
+                Expr::Stat(stat) => manage_statistics(stat, calstack, registers)?,
+                Expr::Dist(dist, func) => manage_distribution(dist, func, calstack)?,
+                Expr::Random(rand) => manage_random(rand, calstack, &mut registers.rng)?,
+                Expr::Integer(func) => manage_number_theory(func, calstack, registers)?,
+                Expr::List(list) => {
+                    let count = list.len();
+                    calstack.extend(list);
+                    calstack.push_back(CalcNum::Number(count as f64));
+                }
+                Expr::Poly(func) => manage_polynomial(func, calstack)?,
+                Expr::Polar(r, theta) => {
+                    calstack.push_back(theta.to_rectangular(&CalcNum::Number(r), degmode)?)
+                }
+                Expr::Program(program) => registers.equation = Some(program),
+                Expr::Algebra(func) => manage_algebra(func, calstack, memory_map)?,
+                Expr::Currency(func) => manage_currency(func, calstack, registers)?,
+                Expr::CashFlow(func) => manage_cashflow(func, calstack, registers)?,
+                Expr::Loan(func) => manage_loan(func, calstack, memory_map, registers)?,
+                Expr::Equation(func) => {
+                    manage_equation(func, calstack, degmode, memory_map, registers)?
+                }
             }
         }
     }
@@ -941,6 +2796,12 @@ fn parse_exp(expression: &str, memo_mode: &mut Option<Memorize>) -> Result<Expr,
             "asin" => Ok(Expr::Monomial(MonomialFunc::ASin)),
             "acos" => Ok(Expr::Monomial(MonomialFunc::ACos)),
             "atan" => Ok(Expr::Monomial(MonomialFunc::ATan)),
+            "sinh" => Ok(Expr::Monomial(MonomialFunc::Sinh)),
+            "cosh" => Ok(Expr::Monomial(MonomialFunc::Cosh)),
+            "tanh" => Ok(Expr::Monomial(MonomialFunc::Tanh)),
+            "asinh" => Ok(Expr::Monomial(MonomialFunc::ASinh)),
+            "acosh" => Ok(Expr::Monomial(MonomialFunc::ACosh)),
+            "atanh" => Ok(Expr::Monomial(MonomialFunc::ATanh)),
             "^" | "pow" => Ok(Expr::Binomial(BinomialFunc::Pow)),
             "sqrt" => Ok(Expr::Monomial(MonomialFunc::Sqrt)),
             "log" => Ok(Expr::Monomial(MonomialFunc::Log)),
@@ -949,20 +2810,144 @@ fn parse_exp(expression: &str, memo_mode: &mut Option<Memorize>) -> Result<Expr,
             "ncr" | "comb" => Ok(Expr::Binomial(BinomialFunc::NCr)),
             "torec" | "torect" | "rec" | "rect" => Ok(Expr::Monomial(MonomialFunc::ToRec)),
             "topol" | "topolar" | "polar" | "pol" => Ok(Expr::Monomial(MonomialFunc::ToPolar)),
+            "drect" => Ok(Expr::Opstack(OperateStack::Rect)),
+            "dpolar" => Ok(Expr::Opstack(OperateStack::Polar)),
+            "beg" | "begin" => Ok(Expr::Opstack(OperateStack::Begin)),
+            "cf0" => Ok(Expr::CashFlow(CashFlow::Initial)),
+            "cfj" => Ok(Expr::CashFlow(CashFlow::Flow)),
+            "nj" => Ok(Expr::CashFlow(CashFlow::Count)),
+            "npv" => Ok(Expr::CashFlow(CashFlow::Npv)),
+            "irr" => Ok(Expr::CashFlow(CashFlow::Irr)),
+            "mirr" => Ok(Expr::CashFlow(CashFlow::Mirr)),
+            "payback" => Ok(Expr::CashFlow(CashFlow::Payback)),
+            "dpayback" => Ok(Expr::CashFlow(CashFlow::DiscountedPayback)),
+            "clcf" => Ok(Expr::CashFlow(CashFlow::Clear)),
+            "amort" => Ok(Expr::Loan(Loan::Amortize)),
+            "amortep" => Ok(Expr::Loan(Loan::EqualPrincipal)),
+            "end" => Ok(Expr::Opstack(OperateStack::End)),
+            "re" => Ok(Expr::Monomial(MonomialFunc::Re)),
+            "im" => Ok(Expr::Monomial(MonomialFunc::Im)),
+            "conj" => Ok(Expr::Monomial(MonomialFunc::Conj)),
+            "arg" => Ok(Expr::Monomial(MonomialFunc::Arg)),
+            "cis" => Ok(Expr::Monomial(MonomialFunc::Cis)),
+            polar if polar.contains(['∠', '@']) => parse_polar(polar),
+            angle if parse_angle(angle).is_some() => parse_angle(angle)
+                .map(Expr::Numbers)
+                .ok_or("Invalid angle".to_string()),
+            money if currency::parse_amount(money).is_some() => currency::parse_amount(money)
+                .map(|(val, code)| Expr::Numbers(CalcNum::Money(val, code)))
+                .ok_or("Invalid amount".to_string()),
+            // ">jpy" は通貨換算
+            target if target.strip_prefix('>').and_then(currency::code).is_some() => target
+                .strip_prefix('>')
+                .and_then(currency::code)
+                .map(|code| Expr::Currency(Currency::Convert(code)))
+                .ok_or("Invalid currency".to_string()),
             "n!" | "!" | "fact" | "factorial" => Ok(Expr::Monomial(MonomialFunc::Factorial)),
+            "gamma" => Ok(Expr::Monomial(MonomialFunc::Gamma)),
+            "lngamma" | "lgamma" => Ok(Expr::Monomial(MonomialFunc::LnGamma)),
+            "beta" => Ok(Expr::Binomial(BinomialFunc::Beta)),
+            "besselj" | "besj" => Ok(Expr::Binomial(BinomialFunc::BesselJ)),
+            "bessely" | "besy" => Ok(Expr::Binomial(BinomialFunc::BesselY)),
+            "erf" => Ok(Expr::Monomial(MonomialFunc::Erf)),
+            "erfc" => Ok(Expr::Monomial(MonomialFunc::Erfc)),
+            "ellipk" => Ok(Expr::Monomial(MonomialFunc::EllipK)),
+            "ellipe" => Ok(Expr::Monomial(MonomialFunc::EllipE)),
+            "zeta" => Ok(Expr::Monomial(MonomialFunc::Zeta)),
             "pi" => Ok(Expr::Const(Constant::Pi)),
             "e" => Ok(Expr::Const(Constant::E)),
+            physical if physical.starts_with('_') => constants::find(&physical[1..])
+                .map(|constant| Expr::Const(Constant::Physical(constant)))
+                .ok_or(format!("Unknown constant {physical}")),
             "sum" => Ok(Expr::Opstack(OperateStack::Sum)),
+            "s+" | "Σ+" => Ok(Expr::Stat(Statistics::Add)),
+            "s2+" | "Σ2+" => Ok(Expr::Stat(Statistics::Add2)),
+            "s-" | "Σ-" => Ok(Expr::Stat(Statistics::Remove)),
+            "s2-" | "Σ2-" => Ok(Expr::Stat(Statistics::Remove2)),
+            "scl" | "clΣ" => Ok(Expr::Stat(Statistics::Clear)),
+            "count" => Ok(Expr::Stat(Statistics::Count)),
+            "mean" => Ok(Expr::Stat(Statistics::Mean)),
+            "sdev" => Ok(Expr::Stat(Statistics::SDev)),
+            "psdev" => Ok(Expr::Stat(Statistics::PSDev)),
+            "var" => Ok(Expr::Stat(Statistics::Var)),
+            "pvar" => Ok(Expr::Stat(Statistics::PVar)),
+            "median" => Ok(Expr::Stat(Statistics::Median)),
+            "min" => Ok(Expr::Stat(Statistics::Min)),
+            "max" => Ok(Expr::Stat(Statistics::Max)),
+            "linfit" | "linr" => Ok(Expr::Stat(Statistics::Fit(Some(FitModel::Linear)))),
+            "expfit" => Ok(Expr::Stat(Statistics::Fit(Some(FitModel::Exponential)))),
+            "logfit" => Ok(Expr::Stat(Statistics::Fit(Some(FitModel::Logarithmic)))),
+            "pwrfit" => Ok(Expr::Stat(Statistics::Fit(Some(FitModel::Power)))),
+            "bestfit" => Ok(Expr::Stat(Statistics::Fit(None))),
+            "corr" => Ok(Expr::Stat(Statistics::Corr)),
+            "r2" => Ok(Expr::Stat(Statistics::RSquared)),
+            "yhat" | "ŷ" => Ok(Expr::Stat(Statistics::YHat)),
+            "xhat" | "x̂" => Ok(Expr::Stat(Statistics::XHat)),
+            "normpdf" => Ok(Expr::Dist(Distribution::Normal, DistFunc::Pdf)),
+            "normcdf" => Ok(Expr::Dist(Distribution::Normal, DistFunc::Cdf)),
+            "norminv" => Ok(Expr::Dist(Distribution::Normal, DistFunc::Inv)),
+            "tpdf" => Ok(Expr::Dist(Distribution::StudentT, DistFunc::Pdf)),
+            "tcdf" => Ok(Expr::Dist(Distribution::StudentT, DistFunc::Cdf)),
+            "tinv" => Ok(Expr::Dist(Distribution::StudentT, DistFunc::Inv)),
+            "chi2pdf" => Ok(Expr::Dist(Distribution::ChiSquare, DistFunc::Pdf)),
+            "chi2cdf" => Ok(Expr::Dist(Distribution::ChiSquare, DistFunc::Cdf)),
+            "chi2inv" => Ok(Expr::Dist(Distribution::ChiSquare, DistFunc::Inv)),
+            "fpdf" => Ok(Expr::Dist(Distribution::F, DistFunc::Pdf)),
+            "fcdf" => Ok(Expr::Dist(Distribution::F, DistFunc::Cdf)),
+            "finv" => Ok(Expr::Dist(Distribution::F, DistFunc::Inv)),
+            "binompdf" => Ok(Expr::Dist(Distribution::Binomial, DistFunc::Pdf)),
+            "binomcdf" => Ok(Expr::Dist(Distribution::Binomial, DistFunc::Cdf)),
+            "binominv" => Ok(Expr::Dist(Distribution::Binomial, DistFunc::Inv)),
+            "poispdf" => Ok(Expr::Dist(Distribution::Poisson, DistFunc::Pdf)),
+            "poiscdf" => Ok(Expr::Dist(Distribution::Poisson, DistFunc::Cdf)),
+            "poisinv" => Ok(Expr::Dist(Distribution::Poisson, DistFunc::Inv)),
+            "rand" => Ok(Expr::Random(Random::Rand)),
+            "randint" => Ok(Expr::Random(Random::RandInt)),
+            "randn" => Ok(Expr::Random(Random::RandN)),
+            "seed" => Ok(Expr::Random(Random::Seed)),
+            "gcd" => Ok(Expr::Integer(NumberTheory::Gcd)),
+            "lcm" => Ok(Expr::Integer(NumberTheory::Lcm)),
+            "isprime" => Ok(Expr::Integer(NumberTheory::IsPrime)),
+            "nextprime" => Ok(Expr::Integer(NumberTheory::NextPrime)),
+            "factor" => Ok(Expr::Integer(NumberTheory::Factor)),
+            "powmod" => Ok(Expr::Integer(NumberTheory::PowMod)),
+            "modinv" => Ok(Expr::Integer(NumberTheory::ModInv)),
+            "totient" | "phi" => Ok(Expr::Integer(NumberTheory::Totient)),
             "torad" => Ok(Expr::Monomial(MonomialFunc::ToRad)),
             "todeg" => Ok(Expr::Monomial(MonomialFunc::ToDeg)),
             "abs" => Ok(Expr::Monomial(MonomialFunc::Abs)),
             "rad" => Ok(Expr::Opstack(OperateStack::Rad)),
             "deg" => Ok(Expr::Opstack(OperateStack::Deg)),
+            "grad" => Ok(Expr::Opstack(OperateStack::Grad)),
+            "turn" => Ok(Expr::Opstack(OperateStack::Turn)),
+            "setmod" => Ok(Expr::Opstack(OperateStack::SetMod)),
+            "clmod" => Ok(Expr::Opstack(OperateStack::ClearMod)),
+            "proots" => Ok(Expr::Poly(Polynomial::Roots)),
+            "peval" => Ok(Expr::Poly(Polynomial::Eval)),
+            list if list.starts_with('[') => parse_list(list),
+            sym if sym.starts_with('\'') => symbolic::parse(sym.trim_matches('\''))
+                .map(|sym| Expr::Numbers(CalcNum::Symbolic(sym))),
+            "simplify" => Ok(Expr::Algebra(Algebra::Simplify)),
+            "expand" => Ok(Expr::Algebra(Algebra::Expand)),
+            "d/dx" => Ok(Expr::Algebra(Algebra::Derivative)),
+            "subst" => Ok(Expr::Algebra(Algebra::Subst)),
+            program if program.starts_with('"') => {
+                Ok(Expr::Program(program.trim_matches('"').trim().to_string()))
+            }
+            "solve" => Ok(Expr::Equation(Equation::Solve)),
+            "bsolve" => Ok(Expr::Equation(Equation::BracketSolve)),
+            "integ" | "∫" => Ok(Expr::Equation(Equation::Integrate)),
+            "deriv" => Ok(Expr::Equation(Equation::Derivative)),
+            "deriv2" => Ok(Expr::Equation(Equation::Derivative2)),
+            "cderiv" => Ok(Expr::Equation(Equation::ComplexStep)),
+            "plot" => Ok(Expr::Equation(Equation::Plot)),
             "tn" => Ok(Expr::Memo(Memorize::Store(Some("n".to_string())))),
             "tiyr" => Ok(Expr::Memo(Memorize::Store(Some("iyr".to_string())))),
             "tpv" => Ok(Expr::Memo(Memorize::Store(Some("pv".to_string())))),
             "tpmt" => Ok(Expr::Memo(Memorize::Store(Some("pmt".to_string())))),
             "tfv" => Ok(Expr::Memo(Memorize::Store(Some("fv".to_string())))),
+            "tpyr" | "p/yr" => Ok(Expr::Memo(Memorize::Store(Some("pyr".to_string())))),
+            "tcyr" | "c/yr" => Ok(Expr::Memo(Memorize::Store(Some("cyr".to_string())))),
             _ => match memo_mode {
                 Some(Memorize::Recall(None)) => {
                     *memo_mode = None;
@@ -1001,13 +2986,131 @@ fn parse_exp(expression: &str, memo_mode: &mut Option<Memorize>) -> Result<Expr,
                         *memo_mode = Some(Memorize::Tvm(None));
                         Ok(Expr::Memo(Memorize::Tvm(None)))
                     }
-                    _ => Ok(Expr::Memo(Memorize::Recall(Some(expression.to_string())))),
+                    _ => match random::parse_dice(expression) {
+                        Some((count, sides)) => Ok(Expr::Random(Random::Dice(count, sides))),
+                        None => Ok(Expr::Memo(Memorize::Recall(Some(expression.to_string())))),
+                    },
                 },
             },
         },
     }
 }
 
+// 式の評価 (RPNプログラムを x の値で実行する)
+// x はメモリの "x" として参照する。元のスタックやメモリには影響しない
+pub fn eval_program(
+    program: &str,
+    x: &CalcNum,
+    degmode: &DegMode,
+    memory_map: &BTreeMap<String, CalcNum>,
+) -> Result<CalcNum, Box<dyn std::error::Error>> {
+    let mut stack = VecDeque::new();
+    let mut memory = memory_map.clone();
+    memory.insert("x".to_string(), x.clone());
+    manage_stack(
+        program,
+        &mut stack,
+        &mut degmode.clone(),
+        &mut memory,
+        &mut None,
+        &mut Registers::default(),
+    )?;
+    stack.pop_back().ok_or("Program returned nothing".into())
+}
+
+// 空白で分割する。[ ] や " ", ' ' で囲まれた部分は一つの要素とする
+fn tokenize(expression: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for c in expression.chars() {
+        match c {
+            '"' | '\'' if depth == 0 && quote.is_none_or(|q| q == c) => {
+                if quote.is_none() && !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                current.push(c);
+                if quote.is_some() {
+                    tokens.push(std::mem::take(&mut current));
+                    quote = None;
+                } else {
+                    quote = Some(c);
+                }
+            }
+            _ if quote.is_some() => current.push(c),
+            '[' => {
+                if depth == 0 && !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                depth += 1;
+                current.push(c);
+            }
+            ']' if depth > 0 => {
+                depth -= 1;
+                current.push(c);
+                if depth == 0 {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+// 単位つきの角度の入力 "30°", "1.2rad", "100grad", "0.25turn"
+fn parse_angle(expression: &str) -> Option<CalcNum> {
+    [
+        ("°", DegMode::Deg),
+        ("deg", DegMode::Deg),
+        ("grad", DegMode::Grad),
+        ("rad", DegMode::Rad),
+        ("turn", DegMode::Turn),
+    ]
+    .into_iter()
+    .find_map(|(suffix, unit)| {
+        let val = expression.strip_suffix(suffix)?.parse::<f64>().ok()?;
+        Some(CalcNum::Angle(val, unit))
+    })
+}
+
+// 極形式の入力 "5∠30" (θは単位がなければ角度モードの単位)
+fn parse_polar(expression: &str) -> Result<Expr, String> {
+    let (r, theta) = expression
+        .split_once(['∠', '@'])
+        .ok_or("Invalid polar number")?;
+    let theta = match theta.parse::<f64>() {
+        Ok(val) => Some(CalcNum::Number(val)),
+        Err(_) => parse_angle(theta),
+    };
+    match (r.parse::<f64>(), theta) {
+        (Ok(r), Some(theta)) => Ok(Expr::Polar(r, theta)),
+        _ => Err("Invalid polar number".to_string()),
+    }
+}
+
+// [1 0 -4] や [1,0,-4] をリストとして読み込む
+fn parse_list(expression: &str) -> Result<Expr, String> {
+    expression
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|item| !item.is_empty())
+        .map(|item| item.to_lowercase().parse::<CalcNum>())
+        .collect::<Result<Vec<_>, _>>()
+        .map(Expr::List)
+        .map_err(|_| "Invalid List".to_string())
+}
+
 // スタックから2つの要素を取り出す
 fn get_two_item(calstack: &mut VecDeque<CalcNum>) -> Result<(CalcNum, CalcNum), String> {
     if calstack.len() < 2 {
@@ -1031,6 +3134,116 @@ fn get_one_item(calstack: &mut VecDeque<CalcNum>) -> Result<CalcNum, String> {
     }
 }
 
+// スタックからn個の実数を取り出す(古い順)。実数でなければスタックを戻す
+fn get_real_items(calstack: &mut VecDeque<CalcNum>, n: usize) -> Result<Vec<f64>, String> {
+    if calstack.len() < n {
+        return Err("Stack is too short".to_string());
+    }
+    let items = calstack.split_off(calstack.len() - n);
+    match items
+        .iter()
+        .map(CalcNum::get_realnumber)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(values) => Ok(values),
+        Err(e) => {
+            calstack.extend(items);
+            Err(e.to_string())
+        }
+    }
+}
+
+// f64で正確に表せる整数の上限 (2^53)
+const MAX_EXACT_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+// スタックからn個の整数を取り出す(古い順)。整数でなければスタックを戻す
+// 2^53 以下の数値か、絶対値が u64 に収まる大きな整数に限る
+fn get_integer_items(calstack: &mut VecDeque<CalcNum>, n: usize) -> Result<Vec<i128>, String> {
+    if calstack.len() < n {
+        return Err("Stack is too short".to_string());
+    }
+    let items = calstack.split_off(calstack.len() - n);
+    let values = items
+        .iter()
+        .map(|x| match x {
+            CalcNum::Number(val) if val.fract() == 0.0 && val.abs() <= MAX_EXACT_INTEGER => {
+                Some(*val as i128)
+            }
+            CalcNum::Integer(val) if val.unsigned_abs() <= u64::MAX as u128 => Some(*val),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+    match values {
+        Some(values) => Ok(values),
+        None => {
+            calstack.extend(items);
+            Err("Exact integer within ±(2^64-1) is required".to_string())
+        }
+    }
+}
+
+// 整数を f64 で正確に表せれば数値、そうでなければ大きな整数としてスタックに置く
+fn exact_integer(val: i128) -> CalcNum {
+    match val.unsigned_abs() <= MAX_EXACT_INTEGER as u128 {
+        true => CalcNum::Number(val as f64),
+        false => CalcNum::Integer(val),
+    }
+}
+
+// 剰余演算モードの二項演算。対象外の演算はNoneを返す
+fn modular_binomial(
+    b_func: &BinomialFunc,
+    a: &CalcNum,
+    b: &CalcNum,
+    m: u64,
+) -> Option<Result<CalcNum, String>> {
+    if !matches!(
+        b_func,
+        BinomialFunc::Add
+            | BinomialFunc::Subtract
+            | BinomialFunc::Multiply
+            | BinomialFunc::Divide
+            | BinomialFunc::Pow
+    ) {
+        return None;
+    }
+    let as_integer = |x: &CalcNum| match x {
+        CalcNum::Number(val) if val.fract() == 0.0 && val.abs() <= MAX_EXACT_INTEGER => {
+            Some(*val as i128)
+        }
+        CalcNum::Integer(val) if val.unsigned_abs() <= u64::MAX as u128 => Some(*val),
+        _ => None,
+    };
+    let (Some(a), Some(b)) = (as_integer(a), as_integer(b)) else {
+        return Some(Err("Modular mode requires integers".to_string()));
+    };
+    // 法が 2^64 近くでもあふれないよう剰余は u64、途中計算は u128 で行う
+    let (ra, rb) = (
+        a.rem_euclid(m as i128) as u64,
+        b.rem_euclid(m as i128) as u64,
+    );
+    let result = match b_func {
+        BinomialFunc::Add => Some(((ra as u128 + rb as u128) % m as u128) as u64),
+        BinomialFunc::Subtract => Some(((ra as u128 + m as u128 - rb as u128) % m as u128) as u64),
+        BinomialFunc::Multiply => Some(numtheory::mul_mod(ra, rb, m)),
+        BinomialFunc::Divide => {
+            numtheory::mod_inverse(rb, m).map(|inv| numtheory::mul_mod(ra, inv, m))
+        }
+        _ if b >= 0 => numtheory::pow_mod(ra, b as u64, m),
+        _ => numtheory::mod_inverse(ra, m)
+            .and_then(|inv| numtheory::pow_mod(inv, b.unsigned_abs() as u64, m)),
+    };
+    Some(match result {
+        Some(value) => Ok(exact_integer(value as i128)),
+        None => Err("No modular inverse".to_string()),
+    })
+}
+
+// 末尾が演算子でも分割しない統計コマンド
+fn is_stat_command(x: &str) -> bool {
+    matches!(x, "s+" | "Σ+" | "s2+" | "Σ2+" | "s-" | "Σ-" | "s2-" | "Σ2-")
+}
+
 fn safe_product(iter: impl IntoIterator<Item = u64>) -> Result<u64, &'static str> {
     iter.into_iter()
         .try_fold(1u64, |acc, x| acc.checked_mul(x).ok_or("over flow"))
@@ -1039,7 +3252,7 @@ fn safe_product(iter: impl IntoIterator<Item = u64>) -> Result<u64, &'static str
 #[cfg(test)]
 mod tests {
 
-    use crate::{CalcNum, DegMode, manage_stack};
+    use crate::{CalcNum, DegMode, calcrpn::Registers, manage_stack};
     use core::f64;
     use std::collections::{BTreeMap, VecDeque};
 
@@ -1049,12 +3262,14 @@ mod tests {
             let mut teststack = VecDeque::new();
             let mut test_memory = BTreeMap::new();
             let mut memomode = None;
+            let mut registers = Registers::default();
             match manage_stack(
                 exp,
                 &mut teststack,
                 &mut DegMode::Rad,
                 &mut test_memory,
                 &mut memomode,
+                &mut registers,
             ) {
                 Ok(_) => (),
                 Err(e) => eprintln!("{e}"),
@@ -1062,6 +3277,9 @@ mod tests {
             match &teststack[0] {
                 CalcNum::Number(data) => (*data, 0.0),
                 CalcNum::Complex(data) => (data.re, data.im),
+                CalcNum::Symbolic(_) => (f64::NAN, f64::NAN),
+                CalcNum::Angle(data, _) | CalcNum::Money(data, _) => (*data, 0.0),
+                CalcNum::Integer(data) => (*data as f64, 0.0),
             }
         };
 
@@ -1107,6 +3325,196 @@ mod tests {
         assert_eq!(realnumtest("10 3 npr"), 720.0);
         assert_eq!(realnumtest("10 3 ncr"), 120.0);
         assert_eq!(realnumtest("5 n!"), 120.0);
+        assert!((realnumtest("0.5 !") - f64::consts::PI.sqrt() / 2.0).abs() < 1e-12);
+        assert!((realnumtest("25 !") / 1.5511210043330986e25 - 1.0).abs() < 1e-12);
+        assert!((realnumtest("5 gamma") - 24.0).abs() < 1e-12);
+        assert!((realnumtest("100 lngamma") - 359.1342053695754).abs() < 1e-9);
+        complex_assert(
+            complexnumtest("-200.5 lngamma"),
+            (-864.7382878706797, f64::consts::PI),
+        );
+        complex_assert(complexnumtest("-1.5 lngamma"), (0.860047015376481, 0.0));
+        complex_assert(
+            complexnumtest("200+1i lngamma"),
+            (857.9311635759358, -0.9873658364391549),
+        );
+        assert!((realnumtest("2 3 beta") - 1.0 / 12.0).abs() < 1e-12);
+        assert_eq!(realnumtest("-3 ! rup"), -3.0);
+        assert!((realnumtest("0.5 erf") - 0.5204998778130465).abs() < 1e-15);
+        assert!((realnumtest("0.5 erfc") - 0.4795001221869535).abs() < 1e-15);
+        assert!((realnumtest("10 5 besselj") + 0.2340615281867936).abs() < 1e-13);
+        assert!((realnumtest("1 2 bessely") + 1.6506826068162544).abs() < 1e-13);
+        assert!((realnumtest("0.5 ellipk") - 1.854074677301372).abs() < 1e-14);
+        assert!((realnumtest("0.5 ellipe") - 1.3506438810476755).abs() < 1e-14);
+        assert!((realnumtest("2 zeta") - f64::consts::PI.powi(2) / 6.0).abs() < 1e-14);
+        assert_eq!(realnumtest("1 zeta"), 1.0);
+        assert_eq!(realnumtest("10 1.5 besselj"), 10.0);
+        assert_eq!(realnumtest("1 -2147483648 besselj"), 1.0);
+        assert_eq!(realnumtest("1 -3e9 bessely"), 1.0);
+        assert!(realnumtest("1e9 0 besselj").abs() < 1e-4);
+        complex_assert(
+            complexnumtest("i !"),
+            (0.498015668118356, -0.1549498283018106),
+        );
+        complex_assert(
+            complexnumtest("-0.5 lngamma"),
+            (1.2655121234846454, f64::consts::PI),
+        );
+        assert_eq!(realnumtest("2 4 4 4 5 5 7 9 mean rup"), 5.0);
+        assert_eq!(realnumtest("2 4 4 4 5 5 7 9 psdev rup"), 2.0);
+        assert_eq!(realnumtest("3 1 2 median rup"), 2.0);
+        assert_eq!(realnumtest("2 s+ 4 s+ 9 s+ cl mean"), 5.0);
+        assert_eq!(realnumtest("2 s+ 4 s+ 9 s+ 4 s- cl max"), 9.0);
+        assert_eq!(realnumtest("1 2 s2+ 3 4 Σ2+ cl count"), 2.0);
+        assert_eq!(realnumtest("1 3 2 5 3 7 linfit"), 1.0);
+        assert_eq!(realnumtest("1 3 2 5 3 7 linfit 10 yhat rup"), 21.0);
+        assert_eq!(
+            realnumtest("1 3 s2+ 2 5 s2+ 3 7 s2+ cl linfit corr rup"),
+            1.0
+        );
+        assert!((realnumtest("0.975 0 1 norminv") - 1.959963984540054).abs() < 1e-9);
+        assert!((realnumtest("2 5 tcdf") - 0.9490302605850706).abs() < 1e-9);
+        assert_eq!(realnumtest("0.5 10 0.5 binominv"), 5.0);
+        assert_eq!(realnumtest("1 2 1.5 binompdf"), 1.0);
+        assert_eq!(realnumtest("42 seed rand"), realnumtest("42 seed rand"));
+        assert_ne!(realnumtest("42 seed rand"), realnumtest("43 seed rand"));
+        assert!((1.0..=6.0).contains(&realnumtest("1 6 randint")));
+        assert!((3.0..=18.0).contains(&realnumtest("3d6")));
+        assert!((4.0..=24.0).contains(&realnumtest("3d6 d6 +")));
+        assert_eq!(realnumtest("12 18 gcd"), 6.0);
+        assert_eq!(realnumtest("-4 6 lcm"), 12.0);
+        assert_eq!(realnumtest("9007199254740881 isprime"), 1.0);
+        assert_eq!(realnumtest("100 nextprime"), 101.0);
+        assert_eq!(realnumtest("4 13 497 powmod"), 445.0);
+        assert_eq!(realnumtest("-3 11 modinv"), 7.0);
+        assert_eq!(realnumtest("36 phi"), 12.0);
+        assert_eq!(realnumtest("120 factor"), 2.0);
+        assert_eq!(realnumtest("120 factor rup"), 5.0);
+        assert_eq!(realnumtest("6 9 modinv"), 6.0);
+        assert_eq!(realnumtest("1.5 2 gcd"), 1.5);
+        // 2^53 を超える整数も丸めずに計算・表示する
+        let exact_stack = |exp| {
+            let mut stack = VecDeque::new();
+            let result = manage_stack(
+                exp,
+                &mut stack,
+                &mut DegMode::Rad,
+                &mut BTreeMap::new(),
+                &mut None,
+                &mut Registers::default(),
+            );
+            (
+                result.is_ok(),
+                stack.iter().map(|x| x.num_format(0)).collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(
+            exact_stack("18446744073709551615 factor"),
+            (
+                true,
+                ["3", "5", "17", "257", "641", "65537", "6700417"]
+                    .map(String::from)
+                    .to_vec()
+            )
+        );
+        assert_eq!(
+            exact_stack("9223372036854775808 nextprime"),
+            (true, vec!["9223372036854775837".to_string()])
+        );
+        assert_eq!(
+            exact_stack("18446744073709551557 isprime"),
+            (true, vec!["1".to_string()])
+        );
+        assert_eq!(
+            exact_stack("2 64 18446744073709551557 powmod"),
+            (true, vec!["59".to_string()])
+        );
+        assert_eq!(
+            exact_stack("4294967296 4294967295 lcm"),
+            (true, vec!["18446744069414584320".to_string()])
+        );
+        assert_eq!(
+            exact_stack("9007199254740993 1 +"),
+            (true, vec!["9007199254740994".to_string()])
+        );
+        assert_eq!(
+            exact_stack("9007199254740993 9007199254740993 * 9007199254740993 / 2 -"),
+            (true, vec!["9007199254740991".to_string()])
+        );
+        assert_eq!(
+            exact_stack("18446744073709551615 1000 %"),
+            (true, vec!["615".to_string()])
+        );
+        assert_eq!(
+            exact_stack("9007199254740993 0.5 +"),
+            (true, vec!["9007199254740992".to_string()])
+        );
+        assert_eq!(
+            exact_stack("18446744073709551616 isprime"),
+            (false, vec!["18446744073709551616".to_string()])
+        );
+        assert_eq!(realnumtest("97 setmod 90 10 +"), 3.0);
+        assert_eq!(realnumtest("97 setmod 3 10 -"), 90.0);
+        assert_eq!(realnumtest("97 setmod 3 2 / 2 *"), 3.0);
+        assert_eq!(realnumtest("97 setmod 3 96 ^"), 1.0);
+        assert_eq!(realnumtest("97 setmod 3 -1 ^ 3 *"), 1.0);
+        assert_eq!(realnumtest("10 setmod 3 4 /"), 3.0);
+        assert_eq!(realnumtest("97 setmod clmod 90 10 +"), 100.0);
+        assert_eq!(realnumtest("97 setmod 10 3 %"), 1.0);
+        assert_eq!(
+            exact_stack("2305843009213693951 setmod 2305843009213693950 2305843009213693950 *"),
+            (true, vec!["1".to_string()])
+        );
+        assert_eq!(
+            exact_stack("2305843009213693951 setmod 2305843009213693950 2 +"),
+            (true, vec!["1".to_string()])
+        );
+        assert_eq!(
+            exact_stack("2305843009213693951 setmod 3 -1 ^"),
+            (true, vec!["1537228672809129301".to_string()])
+        );
+        assert_eq!(
+            exact_stack(
+                "18446744073709551557 setmod 18446744073709551556 18446744073709551556 * 18446744073709551555 +"
+            ),
+            (true, vec!["18446744073709551556".to_string()])
+        );
+        assert_eq!(realnumtest("[1 0 -4] rup"), 3.0);
+        assert_eq!(realnumtest("[1, 0, -4] 3 peval"), 5.0);
+        assert_eq!(realnumtest("1 -3 2 3 proots sum"), 3.0);
+        assert!((realnumtest("[1 0 -4] proots *") + 4.0).abs() < 1e-12);
+        complex_assert(complexnumtest("[1 0 1] proots +"), (0.0, 0.0));
+        complex_assert(complexnumtest("[1 0 1] proots *"), (1.0, 0.0));
+        complex_assert(complexnumtest("[1 0 1] i peval"), (0.0, 0.0));
+        assert_eq!(realnumtest("5 1 2 5 proots"), 5.0);
+        assert!((realnumtest("\"x 2 ^ 2 -\" 1 solve") - 2.0_f64.sqrt()).abs() < 1e-14);
+        assert!((realnumtest("\"x cos x -\" 0 1 bsolve") - 0.7390851332151607).abs() < 1e-14);
+        assert!((realnumtest("3 sto k \"x k ^ 8 -\" 1 solve rup") - 2.0).abs() < 1e-12);
+        assert_eq!(realnumtest("\"x 2 ^ 1 +\" 1 solve"), 1.0);
+        assert_eq!(realnumtest("1 solve"), 1.0);
+        assert!((realnumtest("\"x sin\" 0 pi integ rup") - 2.0).abs() < 1e-12);
+        assert!(
+            (realnumtest("deg \"x sin\" 0 180 integ rup") - 360.0 / f64::consts::PI).abs() < 1e-10
+        );
+        assert!(realnumtest("\"x x *\" 0 3 integ") < 1e-10);
+        assert!((realnumtest("\"x 3 ^\" 2 deriv") - 12.0).abs() < 1e-9);
+        assert!((realnumtest("\"x 3 ^\" 2 deriv2") - 12.0).abs() < 1e-6);
+        assert!(
+            (realnumtest("\"e x ^ x sin /\" 1.5 cderiv") - 4.174_327_534_094_112).abs() < 1e-10
+        );
+        assert_eq!(realnumtest("deg \"x sin\" 1.5 cderiv"), 1.5);
+        assert_eq!(realnumtest("\"x abs\" 1.5 cderiv"), 1.5);
+        assert_eq!(realnumtest("7 \"x sin\" 0 1 plot"), 7.0);
+        assert_eq!(realnumtest("\"x sin\" 1 1 plot"), 1.0);
+        assert_eq!(realnumtest("3 sto x del 'x^2 + 1' subst"), 10.0);
+        assert_eq!(realnumtest("'x' 2 ^ 3 'x' * + 2 sto x del subst"), 10.0);
+        assert_eq!(realnumtest("'x^3' d/dx 2 sto x del subst"), 12.0);
+        assert_eq!(realnumtest("'(x+1)^2' expand 1 sto x del subst"), 4.0);
+        assert_eq!(realnumtest("2 'x' + simplify 5 sto x del subst"), 7.0);
+        assert_eq!(realnumtest("'x' sin 0 sto x del subst"), 0.0);
+        assert_eq!(realnumtest("1 'x' %"), 1.0);
+        assert_eq!(realnumtest("1 simplify"), 1.0);
+        assert_eq!(realnumtest("'2*pi' subst"), 2.0 * f64::consts::PI);
         complex_assert(complexnumtest("-10i log"), (1.0, -0.6821881769));
         complex_assert(
             complexnumtest("-10i ln"),
@@ -1118,6 +3526,18 @@ mod tests {
             (0.6623045446, -0.7522911202),
         );
         complex_assert(complexnumtest("-9 sqrt"), (0.0, 3.0));
+        assert!((realnumtest("1 sinh") - 1.1752011936438014).abs() < 1e-10);
+        assert!((realnumtest("1 cosh") - 1.5430806348152437).abs() < 1e-10);
+        assert!((realnumtest("1 tanh") - 0.7615941559557649).abs() < 1e-10);
+        assert!((realnumtest("1 sinh asinh") - 1.0).abs() < 1e-10);
+        assert!((realnumtest("2 acosh cosh") - 2.0).abs() < 1e-10);
+        assert!((realnumtest("0.5 atanh") - 0.5493061443340549).abs() < 1e-10);
+        complex_assert(complexnumtest("0.5 acosh"), (0.0, f64::consts::PI / 3.0));
+        complex_assert(complexnumtest("2 atanh tanh"), (2.0, 0.0));
+        complex_assert(
+            complexnumtest("1+i sinh"),
+            (0.6349639147847361, 1.2984575814159773),
+        );
         complex_assert(complexnumtest("2 3+4i +"), (5.0, 4.0));
         complex_assert(complexnumtest("2+3i 3+4i +"), (5.0, 7.0));
         complex_assert(complexnumtest("2+3i 3+4i +"), (5.0, 7.0));
@@ -1135,12 +3555,189 @@ mod tests {
         complex_assert(complexnumtest("i i ^"), complexnumtest("e pi -2 / ^"));
         complex_assert(complexnumtest("i i ^"), complexnumtest("e pi -2 / ^"));
         complex_assert(
-            complexnumtest("1 pi 6 / i * + torec"),
+            complexnumtest("1 pi 6 / torec"),
             complexnumtest("pi 6 / cos pi 6 / sin i * +"),
         );
         complex_assert(
             complexnumtest("pi 6 / cos pi 6 / sin i * + topolar"),
-            complexnumtest("1 pi 6 / i * + "),
+            (1.0, 0.0),
+        );
+        complex_assert(
+            complexnumtest("pi 6 / cos pi 6 / sin i * + topolar sw del"),
+            (f64::consts::PI / 6.0, 0.0),
+        );
+        // polar / rec は topolar / torec の別名。表示形式の切り替えは dpolar / drect
+        complex_assert(complexnumtest("3+4i polar"), (5.0, 0.0));
+        complex_assert(complexnumtest("2 0 rec"), (2.0, 0.0));
+        complex_assert(complexnumtest("3+4i dpolar"), (3.0, 4.0));
+        complex_assert(complexnumtest("3+4i re"), (3.0, 0.0));
+        complex_assert(complexnumtest("3+4i im"), (4.0, 0.0));
+        complex_assert(complexnumtest("3+4i conj"), (3.0, -4.0));
+        complex_assert(complexnumtest("-2 arg"), (f64::consts::PI, 0.0));
+        complex_assert(complexnumtest("pi 2 / cis"), (0.0, 1.0));
+        complex_assert(complexnumtest("deg 2∠90"), (0.0, 2.0));
+        complex_assert(complexnumtest("deg 2@90° 2∠0 +"), (2.0, 2.0));
+        complex_assert(complexnumtest("1∠1 1∠-1 *"), (1.0, 0.0));
+        complex_assert(complexnumtest("1 i * cis"), ((-1.0f64).exp(), 0.0));
+        complex_assert(complexnumtest("5 torec"), (5.0, 0.0));
+
+        assert!((realnumtest("grad 100 sin") - 1.0).abs() < 1e-15);
+        assert!((realnumtest("turn 0.25 sin") - 1.0).abs() < 1e-15);
+        assert!((realnumtest("grad 1 atan") - 50.0).abs() < 1e-12);
+        assert!((realnumtest("30° sin") - 0.5).abs() < 1e-15);
+        assert!((realnumtest("100grad cos")).abs() < 1e-15);
+        assert!((realnumtest("deg 0.5 asin rad sin") - 0.5).abs() < 1e-15);
+        assert!((realnumtest("deg 0.5 asin 0.5 asin + sin") - 3f64.sqrt() / 2.0).abs() < 1e-15);
+        assert!((realnumtest("90° 100grad +") - 180.0).abs() < 1e-12);
+        assert!((realnumtest("90° 2 * sin")).abs() < 1e-15);
+        assert!((realnumtest("90° 0.5turn /") - 0.5).abs() < 1e-15);
+        assert!((realnumtest("0.5turn todeg") - 180.0).abs() < 1e-12);
+        assert!((realnumtest("90° sqrt") - 90f64.sqrt()).abs() < 1e-15);
+        complex_assert(complexnumtest("rad 2∠90°"), (0.0, 2.0));
+        complex_assert(
+            complexnumtest("1+1i arg deg cis rad"),
+            (0.5f64.sqrt(), 0.5f64.sqrt()),
+        );
+
+        assert_eq!(realnumtest("_c"), 299_792_458.0);
+        assert_eq!(realnumtest("_NA _kB *"), 8.314_462_618_153_24);
+        assert_eq!(realnumtest("_ħ"), 1.054_571_817e-34);
+        assert!(super::parse_exp("_nope", &mut None).is_err());
+
+        assert_eq!(realnumtest("100usd 2 *"), 200.0);
+        assert_eq!(realnumtest("100usd $50 +"), 150.0);
+        assert_eq!(realnumtest("100usd 25usd /"), 4.0);
+        assert_eq!(realnumtest("100usd 50jpy +"), 100.0);
+        assert_eq!(realnumtest("100usd 2 ^"), 100.0);
+        assert_eq!(realnumtest("3 to x rcl x"), 3.0);
+        // 通貨コードと同じ名前のメモにも保存できる
+        assert_eq!(realnumtest("5 sto usd cl rcl usd"), 5.0);
+        assert_eq!(realnumtest("5 to eur cl rcl eur"), 5.0);
+
+        assert!((realnumtest("12 tn 20 tiyr 22 tpv 0 tfv cl tvm pmt") + 2.038).abs() < 0.01);
+        assert!(
+            (realnumtest("1 tpyr 10 tn 5 tiyr -100 tpv 0 tpmt cl tvm fv") - 162.889).abs() < 0.001
+        );
+        assert!(
+            (realnumtest("2 tcyr 300 tn 6 tiyr 100000 tpv 0 tfv cl tvm pmt") + 639.81).abs() < 0.01
+        );
+        let mut memory = BTreeMap::new();
+        memory.insert("pyr".to_string(), CalcNum::Number(0.0));
+        assert!(super::TvmItem::config(&memory, Default::default()).is_none());
+        assert!(
+            (realnumtest("-1000 cf0 300 cfj 2 nj 400 cfj 500 cfj 10 npv") - 162.694).abs() < 0.001
+        );
+        assert!((realnumtest("-1000 cf0 300 cfj 2 nj 400 cfj 500 cfj irr") - 16.636).abs() < 0.001);
+        assert!(
+            (realnumtest("-1000 cf0 300 cfj 2 nj 400 cfj 500 cfj 10 12 mirr") - 14.947).abs()
+                < 0.001
+        );
+        assert_eq!(
+            realnumtest("-1000 cf0 300 cfj 2 nj 400 cfj 500 cfj payback"),
+            3.0
+        );
+        assert!(
+            (realnumtest("-1000 cf0 300 cfj 2 nj 400 cfj 500 cfj 10 dpayback") - 3.524).abs()
+                < 0.001
+        );
+        // CF0 がなければエラーでスタックはそのまま
+        let mut stack = VecDeque::new();
+        assert!(
+            manage_stack(
+                "7 300 cfj",
+                &mut stack,
+                &mut DegMode::Rad,
+                &mut BTreeMap::new(),
+                &mut None,
+                &mut Registers::default(),
+            )
+            .is_err()
+        );
+        assert!(matches!(
+            stack.make_contiguous(),
+            [CalcNum::Number(7.0), CalcNum::Number(300.0)]
+        ));
+        assert!(
+            (realnumtest("12 tn 12 tiyr 1000 tpv 0 tfv tvm pmt tpmt cl 1 1 amort") + 10.0).abs()
+                < 1e-9
+        );
+        assert!(
+            (realnumtest("12 tn 12 tiyr 1000 tpv 0 tfv tvm pmt tpmt cl 1 12 amort rdn") + 1000.0)
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            (realnumtest("12 tn 12 tiyr 1000 tpv 0 tfv tvm pmt tpmt cl 1 12 amort rdn rdn")).abs()
+                < 1e-9
+        );
+        assert_eq!(
+            realnumtest("12 tn 12 tiyr 1000 tpv 0 tfv tvm pmt tpmt cl 1 13 amort"),
+            1.0
+        );
+        assert_eq!(
+            realnumtest("1e300 tn 12 tiyr 1000 tpv -10 tpmt cl 1 1 amort"),
+            1.0
+        );
+        assert_eq!(
+            realnumtest("inf tn 12 tiyr 1000 tpv -10 tpmt cl 1 1 amort"),
+            1.0
+        );
+        assert!((realnumtest("12 tn 12 tiyr 1000 tpv cl 1 12 amortep") + 65.0).abs() < 1e-9);
+        assert!(
+            (realnumtest("12 tn 12 tiyr 1000 tpv cl 12 12 amortep rdn") + 1000.0 / 12.0).abs()
+                < 1e-9
+        );
+        assert_eq!(realnumtest("12 tn 12 tiyr cl 1 12 amortep"), 1.0);
+        assert_eq!(realnumtest("inf tn 12 tiyr 1000 tpv cl 1 1 amortep"), 1.0);
+        // 範囲外の期ではエラーになり、補足も残さない
+        let mut registers = Registers::default();
+        assert!(
+            manage_stack(
+                "12 tn 12 tiyr 1000 tpv cl 1 13 amortep",
+                &mut VecDeque::new(),
+                &mut DegMode::Rad,
+                &mut BTreeMap::new(),
+                &mut None,
+                &mut registers,
+            )
+            .is_err()
+        );
+        assert!(registers.notice.is_none());
+        assert!(
+            (realnumtest("1 tpyr 10 tn 5 tiyr 0 tpv -100 tpmt cl begin tvm fv") - 1320.679).abs()
+                < 0.001
+        );
+        assert!(
+            (realnumtest("1 tpyr 10 tn 810.782 tpv -100 tpmt 0 tfv cl beg tvm iyr") - 5.0).abs()
+                < 0.001
+        );
+        let mut stack = VecDeque::new();
+        let mut registers = Registers::default();
+        registers.rates.set("USD", "JPY", 150.0, "2026-10-01");
+        manage_stack(
+            "¥3000 >usd 1eur",
+            &mut stack,
+            &mut DegMode::Rad,
+            &mut BTreeMap::new(),
+            &mut None,
+            &mut registers,
+        )
+        .map_err(|e| e.to_string())?;
+        assert!(matches!(stack[0], CalcNum::Money(val, "USD") if val == 20.0));
+        assert_eq!(
+            registers.notice.as_deref(),
+            Some("1 JPY = 0.006666666666666667 USD (2026-10-01)")
+        );
+        assert!(
+            manage_stack(
+                ">jpy",
+                &mut stack,
+                &mut DegMode::Rad,
+                &mut BTreeMap::new(),
+                &mut None,
+                &mut registers,
+            )
+            .is_err()
         );
 
         Ok(())
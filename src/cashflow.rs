@@ -0,0 +1,354 @@
+use crate::finance::{self, RateReport};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Row, Table, TableState},
+};
+
+// キャッシュフロー表 (HP-12C/BA II の CF0, CFj, Nj)
+// flows[0] が CF0。以降は金額 CFj が Nj 回続く
+// 利率はすべて1期あたりの%
+
+const MAX_COUNT: u32 = 999;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flow {
+    pub amount: f64,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CashFlows {
+    pub flows: Vec<Flow>,
+}
+
+impl CashFlows {
+    pub fn set_initial(&mut self, amount: f64) {
+        // CF0 を入れると表を作り直す
+        self.flows = vec![Flow { amount, count: 1 }];
+    }
+
+    pub fn push(&mut self, amount: f64) -> Result<(), String> {
+        if self.flows.is_empty() {
+            return Err("Set CF0 first (cf0)".to_string());
+        }
+        self.flows.push(Flow { amount, count: 1 });
+        Ok(())
+    }
+
+    pub fn set_count(&mut self, index: usize, count: f64) -> Result<(), String> {
+        // CF0 の回数は1に固定
+        if count.fract() != 0.0 || !(1.0..=MAX_COUNT as f64).contains(&count) {
+            return Err(format!("Nj must be an integer from 1 to {MAX_COUNT}"));
+        }
+        match self.flows.get_mut(index) {
+            Some(flow) if index > 0 => {
+                flow.count = count as u32;
+                Ok(())
+            }
+            _ => Err("Nj needs a CFj".to_string()),
+        }
+    }
+
+    pub fn periods(&self) -> usize {
+        // CF0 を除いた期数
+        self.flows
+            .iter()
+            .skip(1)
+            .map(|flow| flow.count as usize)
+            .sum()
+    }
+
+    fn expanded(&self) -> Vec<f64> {
+        // 期ごとの金額 (t = 0, 1, 2, ...)
+        self.flows
+            .iter()
+            .flat_map(|flow| std::iter::repeat_n(flow.amount, flow.count as usize))
+            .collect()
+    }
+
+    fn npv_at(&self, i: f64) -> f64 {
+        self.expanded()
+            .iter()
+            .enumerate()
+            .map(|(t, cf)| cf / (1.0 + i).powi(t as i32))
+            .sum()
+    }
+
+    pub fn npv(&self, rate: f64) -> Result<f64, String> {
+        self.check()?;
+        Ok(self.npv_at(rate / 100.0))
+    }
+
+    pub fn irr(&self) -> Result<RateReport, String> {
+        // NPV = 0 となる利率
+        self.check()?;
+        let (i, roots) = finance::solve_rate(|i| self.npv_at(i), "IRR")?;
+        Ok(RateReport {
+            rate: i * 100.0,
+            roots,
+            name: "IRR",
+        })
+    }
+
+    pub fn mirr(&self, finance_rate: f64, reinvest_rate: f64) -> Result<f64, String> {
+        // 支出は finance_rate で現在価値に、収入は reinvest_rate で最終期の将来価値にする
+        // MIRR = (FV(収入) / -PV(支出))^(1/n) - 1
+        self.check()?;
+        let n = self.periods() as i32;
+        let (f, r) = (finance_rate / 100.0, reinvest_rate / 100.0);
+        let (outflow, inflow) =
+            self.expanded()
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(out, inc), (t, &cf)| match cf < 0.0 {
+                    true => (out + cf / (1.0 + f).powi(t as i32), inc),
+                    false => (out, inc + cf * (1.0 + r).powi(n - t as i32)),
+                });
+        if outflow == 0.0 || inflow == 0.0 {
+            return Err("MIRR needs both negative and positive cash flows".to_string());
+        }
+        Ok(((inflow / -outflow).powf(1.0 / n as f64) - 1.0) * 100.0)
+    }
+
+    pub fn payback(&self, rate: f64) -> Result<f64, String> {
+        // 累積(割引)キャッシュフローが0以上になる期。期の途中は線形補間する
+        // rate = 0 なら単純回収期間
+        self.check()?;
+        let i = rate / 100.0;
+        let mut cumulative = 0.0;
+        for (t, cf) in self.expanded().iter().enumerate() {
+            let discounted = cf / (1.0 + i).powi(t as i32);
+            if cumulative < 0.0 && cumulative + discounted >= 0.0 {
+                return Ok(t as f64 - 1.0 + -cumulative / discounted);
+            }
+            cumulative += discounted;
+        }
+        Err("Not paid back within the cash flows".to_string())
+    }
+
+    fn check(&self) -> Result<(), String> {
+        match self.flows.len() {
+            0 | 1 => Err("Cash flows need CF0 and at least one CFj".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        // パネル下部の表示
+        let irr = match self.irr() {
+            Ok(report) => format!("{:.4}%", report.rate),
+            Err(_) => "-".to_string(),
+        };
+        let payback = match self.payback(0.0) {
+            Ok(t) => format!("{t:.3}"),
+            Err(_) => "-".to_string(),
+        };
+        format!(
+            " n:{} | NPV(0%):{:.2} | IRR:{irr} | Payback:{payback} ",
+            self.periods(),
+            self.npv(0.0).unwrap_or(0.0)
+        )
+    }
+}
+
+fn popup_area(area: Rect) -> Rect {
+    let [area] = Layout::vertical([Constraint::Percentage(80)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(60)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+// 編集中の項目
+enum Editing {
+    Amount(String),
+    Count(String),
+}
+
+pub fn run_editor(
+    terminal: &mut ratatui::DefaultTerminal,
+    cashflows: &mut CashFlows,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // ↑/↓:選択, Enter:金額を編集, n:回数を編集, a:行を追加, d:行を削除, Esc:閉じる
+    // 編集中は Enter で確定、Esc で取り消し
+    if cashflows.flows.is_empty() {
+        cashflows.set_initial(0.0);
+    }
+    let mut state = TableState::default().with_selected(0);
+    let mut editing: Option<Editing> = None;
+    let mut message = String::new();
+    loop {
+        let selected = state.selected().unwrap_or(0);
+        terminal.draw(|frame| {
+            let area = popup_area(frame.area());
+            let rows = cashflows.flows.iter().enumerate().map(|(j, flow)| {
+                let (amount, count) = match (&editing, j == selected) {
+                    (Some(Editing::Amount(buffer)), true) => {
+                        (format!("{buffer}_"), flow.count.to_string())
+                    }
+                    (Some(Editing::Count(buffer)), true) => {
+                        (flow.amount.to_string(), format!("{buffer}_"))
+                    }
+                    _ => (flow.amount.to_string(), flow.count.to_string()),
+                };
+                Row::new([format!("CF{j}"), amount, count])
+            });
+            let title_bottom = match message.is_empty() {
+                true => " ↑/↓:Select Enter:Amount n:Nj a:Add d:Delete Esc:Close ".to_string(),
+                false => format!(" {message} "),
+            };
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Length(6),
+                    Constraint::Fill(1),
+                    Constraint::Length(6),
+                ],
+            )
+            .header(
+                Row::new(["j", "CFj", "Nj"]).style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(
+                Block::default()
+                    .title(" Cash Flows ")
+                    .title(cashflows.summary())
+                    .title_bottom(title_bottom)
+                    .borders(Borders::ALL),
+            );
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(table, area, &mut state);
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        message.clear();
+        match editing.as_mut() {
+            Some(Editing::Amount(buffer) | Editing::Count(buffer)) => match key.code {
+                KeyCode::Char(c) => buffer.push(c),
+                KeyCode::Backspace => {
+                    buffer.pop();
+                }
+                KeyCode::Esc => editing = None,
+                KeyCode::Enter => {
+                    let result = match editing.take() {
+                        Some(Editing::Amount(buffer)) => match buffer.trim().parse::<f64>() {
+                            Ok(amount) => {
+                                cashflows.flows[selected].amount = amount;
+                                Ok(())
+                            }
+                            Err(e) => Err(e.to_string()),
+                        },
+                        Some(Editing::Count(buffer)) => match buffer.trim().parse::<f64>() {
+                            Ok(count) => cashflows.set_count(selected, count),
+                            Err(e) => Err(e.to_string()),
+                        },
+                        None => Ok(()),
+                    };
+                    if let Err(e) = result {
+                        message = format!("Error: {e}");
+                    }
+                }
+                _ => (),
+            },
+            None => {
+                if browse_key(key.code, cashflows, &mut state, &mut editing)? {
+                    break;
+                }
+            }
+        }
+    }
+    terminal.clear()?;
+    Ok(())
+}
+
+// 編集中でないときのキー操作、true なら閉じる
+fn browse_key(
+    code: KeyCode,
+    cashflows: &mut CashFlows,
+    state: &mut TableState,
+    editing: &mut Option<Editing>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    // 選択位置は常に最終行までに収める
+    let last = cashflows.flows.len().saturating_sub(1);
+    let selected = state.selected().unwrap_or(0).min(last);
+    match code {
+        KeyCode::Esc | KeyCode::Char('q') => return Ok(true),
+        KeyCode::Up => state.select(Some(selected.saturating_sub(1))),
+        KeyCode::Down => state.select(Some((selected + 1).min(last))),
+        KeyCode::Enter => *editing = Some(Editing::Amount(String::new())),
+        KeyCode::Char('n') if selected > 0 => *editing = Some(Editing::Count(String::new())),
+        KeyCode::Char('a') => {
+            cashflows.push(0.0)?;
+            state.select(Some(cashflows.flows.len() - 1));
+            *editing = Some(Editing::Amount(String::new()));
+        }
+        KeyCode::Char('d') | KeyCode::Delete if selected > 0 => {
+            cashflows.flows.remove(selected);
+            state.select(Some(selected.min(cashflows.flows.len() - 1)));
+        }
+        _ => (),
+    }
+    Ok(false)
+}
+
+#[test]
+fn cashflow_test() {
+    let mut cf = CashFlows::default();
+    assert!(cf.push(100.0).is_err());
+    cf.set_initial(-1000.0);
+    assert!(cf.irr().is_err());
+    cf.push(300.0).unwrap();
+    cf.set_count(1, 2.0).unwrap();
+    cf.push(400.0).unwrap();
+    cf.push(500.0).unwrap();
+    assert!(cf.set_count(0, 2.0).is_err());
+    assert!(cf.set_count(1, 1.5).is_err());
+    assert_eq!(cf.periods(), 4);
+    assert_eq!(cf.expanded(), vec![-1000.0, 300.0, 300.0, 400.0, 500.0]);
+
+    assert_eq!(cf.npv(0.0).unwrap(), 500.0);
+    assert!((cf.npv(10.0).unwrap() - 162.694).abs() < 0.001);
+    let irr = cf.irr().unwrap();
+    assert!((irr.rate - 16.636).abs() < 0.001);
+    assert!(cf.npv(irr.rate).unwrap().abs() < 1e-9);
+    assert_eq!(irr.roots, 1);
+    assert!((cf.mirr(10.0, 12.0).unwrap() - 14.947).abs() < 0.001);
+    // 300 + 300 + 400 = 1000 でちょうど3期
+    assert_eq!(cf.payback(0.0).unwrap(), 3.0);
+    assert!((cf.payback(10.0).unwrap() - 3.524).abs() < 0.001);
+
+    // 回収できない
+    cf.set_initial(-1000.0);
+    cf.push(100.0).unwrap();
+    assert!(cf.payback(0.0).is_err());
+    assert!(cf.mirr(10.0, 10.0).is_ok());
+    cf.set_initial(100.0);
+    cf.push(100.0).unwrap();
+    assert!(cf.irr().is_err());
+    assert!(cf.mirr(10.0, 10.0).is_err());
+
+    // 最終行を越えて↓を押してから削除しても落ちない
+    let mut cf = CashFlows::default();
+    cf.set_initial(-1000.0);
+    cf.push(300.0).unwrap();
+    cf.push(400.0).unwrap();
+    let mut state = TableState::default().with_selected(0);
+    let mut editing = None;
+    for _ in 0..5 {
+        browse_key(KeyCode::Down, &mut cf, &mut state, &mut editing).unwrap();
+    }
+    assert_eq!(state.selected(), Some(2));
+    browse_key(KeyCode::Char('d'), &mut cf, &mut state, &mut editing).unwrap();
+    assert_eq!(cf.expanded(), vec![-1000.0, 300.0]);
+    assert_eq!(state.selected(), Some(1));
+    assert!(browse_key(KeyCode::Esc, &mut cf, &mut state, &mut editing).unwrap());
+}
@@ -0,0 +1,349 @@
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Borders, Clear, Row, Table, TableState},
+};
+
+// 物理定数 (CODATA 2018)
+// スタックには "_c" のように _ + 記号で置く。const で一覧・検索できる。
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhysConst {
+    pub symbol: &'static str,
+    pub display: &'static str,
+    pub name: &'static str,
+    pub value: f64,
+    // 標準不確かさ (定義値は0)
+    pub uncertainty: f64,
+    pub unit: &'static str,
+}
+
+impl PhysConst {
+    pub fn summary(&self) -> String {
+        let uncertainty = match self.uncertainty {
+            0.0 => "(exact)".to_string(),
+            u => format!("± {u:e}"),
+        };
+        format!(
+            "{} {} = {:e} {} {}",
+            self.display, self.name, self.value, self.unit, uncertainty
+        )
+    }
+}
+
+const fn constant(
+    symbol: &'static str,
+    display: &'static str,
+    name: &'static str,
+    value: f64,
+    uncertainty: f64,
+    unit: &'static str,
+) -> PhysConst {
+    PhysConst {
+        symbol,
+        display,
+        name,
+        value,
+        uncertainty,
+        unit,
+    }
+}
+
+pub const CATALOG: [PhysConst; 26] = [
+    constant(
+        "c",
+        "c",
+        "speed of light in vacuum",
+        299_792_458.0,
+        0.0,
+        "m s^-1",
+    ),
+    constant("h", "h", "Planck constant", 6.626_070_15e-34, 0.0, "J s"),
+    constant(
+        "hbar",
+        "ħ",
+        "reduced Planck constant",
+        1.054_571_817e-34,
+        0.0,
+        "J s",
+    ),
+    constant(
+        "kB",
+        "k_B",
+        "Boltzmann constant",
+        1.380_649e-23,
+        0.0,
+        "J K^-1",
+    ),
+    constant(
+        "NA",
+        "N_A",
+        "Avogadro constant",
+        6.022_140_76e23,
+        0.0,
+        "mol^-1",
+    ),
+    constant(
+        "G",
+        "G",
+        "Newtonian constant of gravitation",
+        6.674_30e-11,
+        1.5e-15,
+        "m^3 kg^-1 s^-2",
+    ),
+    constant("e", "e", "elementary charge", 1.602_176_634e-19, 0.0, "C"),
+    constant(
+        "me",
+        "m_e",
+        "electron mass",
+        9.109_383_701_5e-31,
+        2.8e-40,
+        "kg",
+    ),
+    constant(
+        "mp",
+        "m_p",
+        "proton mass",
+        1.672_621_923_69e-27,
+        5.1e-37,
+        "kg",
+    ),
+    constant(
+        "mn",
+        "m_n",
+        "neutron mass",
+        1.674_927_498_04e-27,
+        9.5e-37,
+        "kg",
+    ),
+    constant(
+        "u",
+        "u",
+        "atomic mass constant",
+        1.660_539_066_60e-27,
+        5.0e-37,
+        "kg",
+    ),
+    constant(
+        "eps0",
+        "ε0",
+        "vacuum electric permittivity",
+        8.854_187_812_8e-12,
+        1.3e-21,
+        "F m^-1",
+    ),
+    constant(
+        "mu0",
+        "μ0",
+        "vacuum magnetic permeability",
+        1.256_637_062_12e-6,
+        1.9e-16,
+        "N A^-2",
+    ),
+    constant(
+        "Z0",
+        "Z0",
+        "characteristic impedance of vacuum",
+        376.730_313_668,
+        5.7e-8,
+        "Ω",
+    ),
+    constant(
+        "R",
+        "R",
+        "molar gas constant",
+        8.314_462_618,
+        0.0,
+        "J mol^-1 K^-1",
+    ),
+    constant("F", "F", "Faraday constant", 96_485.332_12, 0.0, "C mol^-1"),
+    constant(
+        "Vm",
+        "V_m",
+        "molar volume of ideal gas (273.15 K, 101.325 kPa)",
+        22.413_969_54e-3,
+        0.0,
+        "m^3 mol^-1",
+    ),
+    constant(
+        "sigma",
+        "σ",
+        "Stefan-Boltzmann constant",
+        5.670_374_419e-8,
+        0.0,
+        "W m^-2 K^-4",
+    ),
+    constant(
+        "alpha",
+        "α",
+        "fine-structure constant",
+        7.297_352_569_3e-3,
+        1.1e-12,
+        "",
+    ),
+    constant(
+        "a0",
+        "a0",
+        "Bohr radius",
+        5.291_772_109_03e-11,
+        8.0e-21,
+        "m",
+    ),
+    constant(
+        "Rinf",
+        "R∞",
+        "Rydberg constant",
+        10_973_731.568_16,
+        2.1e-5,
+        "m^-1",
+    ),
+    constant(
+        "muB",
+        "μB",
+        "Bohr magneton",
+        9.274_010_078_3e-24,
+        2.8e-33,
+        "J T^-1",
+    ),
+    constant(
+        "Phi0",
+        "Φ0",
+        "magnetic flux quantum",
+        2.067_833_848e-15,
+        0.0,
+        "Wb",
+    ),
+    constant("eV", "eV", "electron volt", 1.602_176_634e-19, 0.0, "J"),
+    constant(
+        "g0",
+        "g0",
+        "standard acceleration of gravity",
+        9.806_65,
+        0.0,
+        "m s^-2",
+    ),
+    constant("atm", "atm", "standard atmosphere", 101_325.0, 0.0, "Pa"),
+];
+
+pub fn find(symbol: &str) -> Option<&'static PhysConst> {
+    // 記号(または表示記号)の完全一致。大文字小文字は区別する (G と g0 など)
+    CATALOG
+        .iter()
+        .find(|c| c.symbol == symbol || c.display == symbol)
+}
+
+pub fn search(query: &str) -> Vec<&'static PhysConst> {
+    // 記号・名前・単位の部分一致 (大文字小文字は区別しない)
+    let query = query.to_lowercase();
+    CATALOG
+        .iter()
+        .filter(|c| {
+            [c.symbol, c.display, c.name, c.unit]
+                .iter()
+                .any(|field| field.to_lowercase().contains(&query))
+        })
+        .collect()
+}
+
+fn popup_area(area: Rect) -> Rect {
+    let [area] = Layout::vertical([Constraint::Percentage(80)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(90)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+pub fn run_browser(
+    terminal: &mut ratatui::DefaultTerminal,
+    query: &str,
+) -> Result<Option<&'static PhysConst>, Box<dyn std::error::Error>> {
+    // 定数の一覧。文字入力で絞り込み、↑/↓で選択、Enterでスタックに置く、Escで閉じる
+    let mut query = query.to_string();
+    let mut state = TableState::default().with_selected(0);
+    let selected = loop {
+        let found = search(&query);
+        if state.selected().is_some_and(|i| i >= found.len()) {
+            state.select(Some(found.len().saturating_sub(1)));
+        }
+        terminal.draw(|frame| {
+            let area = popup_area(frame.area());
+            let rows = found.iter().map(|c| {
+                let uncertainty = match c.uncertainty {
+                    0.0 => "exact".to_string(),
+                    u => format!("{u:e}"),
+                };
+                Row::new([
+                    format!("_{}", c.symbol),
+                    c.display.to_string(),
+                    c.name.to_string(),
+                    format!("{:e}", c.value),
+                    uncertainty,
+                    c.unit.to_string(),
+                ])
+            });
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Length(7),
+                    Constraint::Length(4),
+                    Constraint::Fill(1),
+                    Constraint::Length(22),
+                    Constraint::Length(8),
+                    Constraint::Length(15),
+                ],
+            )
+            .header(
+                Row::new(["Input", "Sym", "Name", "Value", "Uncert.", "Unit"])
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+            )
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .block(
+                Block::default()
+                    .title(format!(" Constants (CODATA 2018) search: {query}_ "))
+                    .title_bottom(" type:Search ↑/↓:Select Enter:Push Esc:Close ")
+                    .borders(Borders::ALL),
+            );
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(table, area, &mut state);
+        })?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Esc => break None,
+                KeyCode::Enter => break state.selected().and_then(|i| found.get(i).copied()),
+                KeyCode::Up => state.select_previous(),
+                KeyCode::Down => state.select_next(),
+                KeyCode::Backspace => {
+                    query.pop();
+                }
+                KeyCode::Char(c) => query.push(c),
+                _ => (),
+            }
+        }
+    };
+    terminal.clear()?;
+    Ok(selected)
+}
+
+#[test]
+fn constants_test() {
+    assert_eq!(find("c").map(|c| c.value), Some(299_792_458.0));
+    assert_eq!(find("ħ").map(|c| c.symbol), Some("hbar"));
+    assert!(find("C").is_none());
+    let hbar = find("hbar").unwrap().value;
+    let h = find("h").unwrap().value;
+    assert!((hbar - h / std::f64::consts::TAU).abs() / hbar < 1e-9);
+
+    let found = search("planck");
+    assert_eq!(found.len(), 2);
+    assert!(search("MASS").iter().any(|c| c.symbol == "me"));
+    assert!(search("zzz").is_empty());
+    assert_eq!(search("").len(), CATALOG.len());
+    assert!(find("G").unwrap().summary().contains("± 1.5e-15"));
+    assert!(find("c").unwrap().summary().contains("(exact)"));
+}
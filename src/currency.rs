@@ -0,0 +1,240 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 通貨換算
+// レートは設定ディレクトリの rates.txt に "USD JPY 150.2 2026-10-18" の形式で保存する。
+// ネットワークは使わない。rate コマンドで手入力したレートだけを使う。
+
+pub const CODES: [&str; 16] = [
+    "USD", "JPY", "EUR", "GBP", "CNY", "KRW", "TWD", "HKD", "SGD", "AUD", "NZD", "CAD", "CHF",
+    "SEK", "INR", "THB",
+];
+
+// 金額の前に付ける通貨記号
+const SYMBOLS: [(char, &str); 4] = [('$', "USD"), ('¥', "JPY"), ('€', "EUR"), ('£', "GBP")];
+
+pub fn code(text: &str) -> Option<&'static str> {
+    // 通貨コード (大文字小文字は区別しない)
+    CODES
+        .into_iter()
+        .find(|code| code.eq_ignore_ascii_case(text))
+}
+
+pub fn parse_amount(text: &str) -> Option<(f64, &'static str)> {
+    // "100usd", "100JPY", "$100", "¥1500" などを金額と通貨に分ける
+    let symbol = SYMBOLS.iter().find_map(|(symbol, code)| {
+        let (sign, rest) = match text.strip_prefix('-') {
+            Some(rest) => (-1.0, rest),
+            None => (1.0, text),
+        };
+        let val = rest.strip_prefix(*symbol)?.parse::<f64>().ok()?;
+        Some((sign * val, *code))
+    });
+    symbol.or_else(|| {
+        let split = text.len().checked_sub(3)?;
+        let (val, suffix) = (text.get(..split)?, text.get(split..)?);
+        Some((val.parse::<f64>().ok()?, code(suffix)?))
+    })
+}
+
+pub fn decimals(code: &str) -> usize {
+    // 補助単位のない通貨は小数点以下を表示しない
+    match code {
+        "JPY" | "KRW" => 0,
+        _ => 2,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rate {
+    pub from: &'static str,
+    pub to: &'static str,
+    // 1 from = rate to
+    pub rate: f64,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateTable {
+    pub rates: Vec<Rate>,
+}
+
+impl RateTable {
+    pub fn set(&mut self, from: &'static str, to: &'static str, rate: f64, date: &str) {
+        // 同じ組 (逆向きも含む) のレートは置き換える
+        self.rates
+            .retain(|r| !((r.from == from && r.to == to) || (r.from == to && r.to == from)));
+        self.rates.push(Rate {
+            from,
+            to,
+            rate,
+            date: date.to_string(),
+        });
+    }
+
+    fn direct(&self, from: &str, to: &str) -> Option<(f64, String)> {
+        self.rates.iter().find_map(|r| {
+            if r.from == from && r.to == to {
+                Some((r.rate, r.date.clone()))
+            } else if r.from == to && r.to == from {
+                Some((1.0 / r.rate, r.date.clone()))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn lookup(&self, from: &str, to: &str) -> Option<(f64, String)> {
+        // 直接のレート、逆数、または一つの通貨を経由したクロスレート
+        // クロスレートの日付は古い方
+        if from == to {
+            return Some((1.0, String::new()));
+        }
+        self.direct(from, to).or_else(|| {
+            CODES.into_iter().find_map(|via| {
+                let (first, first_date) = self.direct(from, via)?;
+                let (second, second_date) = self.direct(via, to)?;
+                Some((first * second, first_date.min(second_date)))
+            })
+        })
+    }
+
+    pub fn convert(
+        &self,
+        amount: f64,
+        from: &str,
+        to: &str,
+    ) -> Result<(f64, String), Box<dyn std::error::Error>> {
+        match self.lookup(from, to) {
+            Some((rate, date)) => Ok((amount * rate, date)),
+            None => Err(format!("No rate for {from}/{to} (rate {from} {to} <value>)").into()),
+        }
+    }
+
+    pub fn parse(text: &str) -> Self {
+        // 読めない行や#で始まる行は無視する
+        // rate コマンドと同じく、正の有限なレートで異なる通貨の組だけを読む
+        let mut table = RateTable::default();
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let items = line.split_whitespace().collect::<Vec<_>>();
+            if let [from, to, rate, date] = items[..]
+                && let (Some(from), Some(to), Ok(rate)) =
+                    (code(from), code(to), rate.parse::<f64>())
+                && rate > 0.0
+                && rate.is_finite()
+                && from != to
+            {
+                table.set(from, to, rate, date);
+            }
+        }
+        table
+    }
+
+    pub fn serialize(&self) -> String {
+        let lines = self
+            .rates
+            .iter()
+            .map(|r| format!("{} {} {} {}\n", r.from, r.to, r.rate, r.date))
+            .collect::<String>();
+        format!("# calrpn rate table: FROM TO RATE DATE (1 FROM = RATE TO)\n{lines}")
+    }
+
+    pub fn summary(&self) -> String {
+        match self.rates.is_empty() {
+            true => "No rates (rate usd jpy 150.2)".to_string(),
+            false => self
+                .rates
+                .iter()
+                .map(|r| format!("{}/{} {} ({})", r.from, r.to, r.rate, r.date))
+                .collect::<Vec<_>>()
+                .join(" | "),
+        }
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("calrpn").join("rates.txt"))
+    }
+
+    pub fn load() -> Self {
+        // ファイルがなければ空の表
+        match Self::path().and_then(|path| std::fs::read_to_string(path).ok()) {
+            Some(text) => Self::parse(&text),
+            None => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("Config directory not found")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.serialize())?;
+        Ok(())
+    }
+}
+
+pub fn today() -> String {
+    // UTCの日付 (YYYY-MM-DD)
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days(days as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // 1970-01-01からの日数を年月日に変換する (グレゴリオ暦)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[test]
+fn currency_test() {
+    assert_eq!(code("usd"), Some("USD"));
+    assert_eq!(code("xyz"), None);
+    assert_eq!(parse_amount("100usd"), Some((100.0, "USD")));
+    assert_eq!(parse_amount("-2.5EUR"), Some((-2.5, "EUR")));
+    assert_eq!(parse_amount("¥1500"), Some((1500.0, "JPY")));
+    assert_eq!(parse_amount("-$3"), Some((-3.0, "USD")));
+    assert_eq!(parse_amount("usd"), None);
+    assert_eq!(parse_amount("30deg"), None);
+
+    let mut table = RateTable::default();
+    table.set("USD", "JPY", 150.0, "2026-10-01");
+    table.set("EUR", "USD", 1.1, "2026-09-30");
+    assert_eq!(
+        table.lookup("USD", "JPY"),
+        Some((150.0, "2026-10-01".into()))
+    );
+    assert_eq!(table.lookup("JPY", "USD").map(|r| r.0), Some(1.0 / 150.0));
+    // EUR -> USD -> JPY
+    let (rate, date) = table.lookup("EUR", "JPY").unwrap();
+    assert!((rate - 165.0).abs() < 1e-9);
+    assert_eq!(date, "2026-09-30");
+    assert!(table.convert(1.0, "GBP", "JPY").is_err());
+    table.set("JPY", "USD", 1.0 / 160.0, "2026-10-02");
+    assert_eq!(table.rates.len(), 2);
+    assert_eq!(RateTable::parse(&table.serialize()), table);
+    assert_eq!(
+        RateTable::parse(
+            "# comment\nUSD JPY x 2026\nfoo\nUSD JPY 0 2026\nEUR USD -1 2026\nUSD USD 2 2026"
+        )
+        .rates
+        .len(),
+        0
+    );
+
+    assert_eq!(civil_from_days(0), (1970, 1, 1));
+    assert_eq!(civil_from_days(20_744), (2026, 10, 18));
+    assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    assert_eq!(today().len(), 10);
+}
@@ -0,0 +1,290 @@
+use crate::special::{beta_inc, erfc, gamma_p, gamma_q, ln_beta, ln_gamma};
+use core::f64;
+use std::cmp::Ordering;
+
+// 正規分布
+pub fn normal_pdf(x: f64, mu: f64, sigma: f64) -> Option<f64> {
+    if sigma <= 0.0 {
+        return None;
+    }
+    let z = (x - mu) / sigma;
+    Some((-0.5 * z * z).exp() / (sigma * (2.0 * f64::consts::PI).sqrt()))
+}
+
+pub fn normal_cdf(x: f64, mu: f64, sigma: f64) -> Option<f64> {
+    if sigma <= 0.0 {
+        return None;
+    }
+    Some(0.5 * erfc(-(x - mu) / (sigma * f64::consts::SQRT_2)))
+}
+
+pub fn normal_inv(p: f64, mu: f64, sigma: f64) -> Option<f64> {
+    if sigma <= 0.0 {
+        return None;
+    }
+    invert_continuous(|x| normal_cdf(x, mu, sigma), p, mu - sigma, mu + sigma)
+}
+
+// スチューデントのt分布
+pub fn t_pdf(x: f64, nu: f64) -> Option<f64> {
+    if nu <= 0.0 {
+        return None;
+    }
+    let ln_pdf =
+        -0.5 * (nu + 1.0) * (1.0 + x * x / nu).ln() - 0.5 * nu.ln() - ln_beta(0.5 * nu, 0.5);
+    Some(ln_pdf.exp())
+}
+
+pub fn t_cdf(x: f64, nu: f64) -> Option<f64> {
+    if nu <= 0.0 {
+        return None;
+    }
+    let tail = 0.5 * beta_inc(0.5 * nu, 0.5, nu / (nu + x * x))?;
+    Some(if x > 0.0 { 1.0 - tail } else { tail })
+}
+
+pub fn t_inv(p: f64, nu: f64) -> Option<f64> {
+    if nu <= 0.0 {
+        return None;
+    }
+    invert_continuous(|x| t_cdf(x, nu), p, -1.0, 1.0)
+}
+
+// カイ二乗分布
+pub fn chi2_pdf(x: f64, k: f64) -> Option<f64> {
+    if k <= 0.0 {
+        return None;
+    }
+    if x < 0.0 {
+        return Some(0.0);
+    }
+    // x = 0 では x^(k/2-1) の極限をとる (0 * ln 0 を避ける)
+    let ln_x_term = match (x == 0.0, k.partial_cmp(&2.0)) {
+        (true, Some(Ordering::Greater)) => return Some(0.0),
+        (true, Some(Ordering::Less)) => return Some(f64::INFINITY),
+        (true, _) => 0.0,
+        (false, _) => (0.5 * k - 1.0) * x.ln(),
+    };
+    let ln_pdf = ln_x_term - 0.5 * x - 0.5 * k * 2.0_f64.ln() - ln_gamma(0.5 * k);
+    Some(ln_pdf.exp())
+}
+
+pub fn chi2_cdf(x: f64, k: f64) -> Option<f64> {
+    if k <= 0.0 {
+        return None;
+    }
+    if x <= 0.0 {
+        return Some(0.0);
+    }
+    gamma_p(0.5 * k, 0.5 * x)
+}
+
+pub fn chi2_inv(p: f64, k: f64) -> Option<f64> {
+    if k <= 0.0 {
+        return None;
+    }
+    invert_continuous(|x| chi2_cdf(x, k), p, 0.0, k)
+}
+
+// F分布
+pub fn f_pdf(x: f64, d1: f64, d2: f64) -> Option<f64> {
+    if d1 <= 0.0 || d2 <= 0.0 {
+        return None;
+    }
+    if x < 0.0 {
+        return Some(0.0);
+    }
+    let ln_x_term = match (x == 0.0, d1.partial_cmp(&2.0)) {
+        (true, Some(Ordering::Greater)) => return Some(0.0),
+        (true, Some(Ordering::Less)) => return Some(f64::INFINITY),
+        (true, _) => 0.0,
+        (false, _) => (0.5 * d1 - 1.0) * x.ln(),
+    };
+    let ln_pdf = 0.5 * d1 * (d1 / d2).ln() + ln_x_term
+        - 0.5 * (d1 + d2) * (1.0 + d1 * x / d2).ln()
+        - ln_beta(0.5 * d1, 0.5 * d2);
+    Some(ln_pdf.exp())
+}
+
+pub fn f_cdf(x: f64, d1: f64, d2: f64) -> Option<f64> {
+    if d1 <= 0.0 || d2 <= 0.0 {
+        return None;
+    }
+    if x <= 0.0 {
+        return Some(0.0);
+    }
+    beta_inc(0.5 * d1, 0.5 * d2, d1 * x / (d1 * x + d2))
+}
+
+pub fn f_inv(p: f64, d1: f64, d2: f64) -> Option<f64> {
+    if d1 <= 0.0 || d2 <= 0.0 {
+        return None;
+    }
+    invert_continuous(|x| f_cdf(x, d1, d2), p, 0.0, 1.0)
+}
+
+// 二項分布
+pub fn binomial_pmf(k: f64, n: f64, p: f64) -> Option<f64> {
+    if !is_count(n) || !(0.0..=1.0).contains(&p) {
+        return None;
+    }
+    if !is_count(k) || k > n {
+        return Some(0.0);
+    }
+    if p == 0.0 || p == 1.0 {
+        let certain = if p == 0.0 { 0.0 } else { n };
+        return Some(if k == certain { 1.0 } else { 0.0 });
+    }
+    let ln_comb = ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0);
+    Some((ln_comb + k * p.ln() + (n - k) * (1.0 - p).ln()).exp())
+}
+
+pub fn binomial_cdf(k: f64, n: f64, p: f64) -> Option<f64> {
+    if !is_count(n) || !(0.0..=1.0).contains(&p) {
+        return None;
+    }
+    if k < 0.0 {
+        return Some(0.0);
+    }
+    let k = k.floor();
+    if k >= n {
+        return Some(1.0);
+    }
+    // P(X ≤ k) = I_(1-p)(n-k, k+1)
+    beta_inc(n - k, k + 1.0, 1.0 - p)
+}
+
+pub fn binomial_inv(q: f64, n: f64, p: f64) -> Option<f64> {
+    if !is_count(n) {
+        return None;
+    }
+    invert_discrete(|k| binomial_cdf(k, n, p), q, n)
+}
+
+// ポアソン分布
+pub fn poisson_pmf(k: f64, lambda: f64) -> Option<f64> {
+    if lambda <= 0.0 {
+        return None;
+    }
+    if !is_count(k) {
+        return Some(0.0);
+    }
+    Some((k * lambda.ln() - lambda - ln_gamma(k + 1.0)).exp())
+}
+
+pub fn poisson_cdf(k: f64, lambda: f64) -> Option<f64> {
+    if lambda <= 0.0 {
+        return None;
+    }
+    if k < 0.0 {
+        return Some(0.0);
+    }
+    // P(X ≤ k) = Q(k+1, λ)
+    gamma_q(k.floor() + 1.0, lambda)
+}
+
+pub fn poisson_inv(q: f64, lambda: f64) -> Option<f64> {
+    if lambda <= 0.0 {
+        return None;
+    }
+    invert_discrete(|k| poisson_cdf(k, lambda), q, f64::INFINITY)
+}
+
+fn is_count(n: f64) -> bool {
+    n >= 0.0 && n.fract() == 0.0
+}
+
+fn invert_continuous(
+    cdf: impl Fn(f64) -> Option<f64>,
+    p: f64,
+    mut low: f64,
+    mut high: f64,
+) -> Option<f64> {
+    // 累積分布関数の逆関数
+    // 区間を広げて根を挟み込み、二分法で求める。
+    if !(0.0 < p && p < 1.0) {
+        return None;
+    }
+    let width = (high - low).max(1.0);
+    let mut step = width;
+    while cdf(low)? > p {
+        low -= step;
+        step *= 2.0;
+    }
+    step = width;
+    while cdf(high)? < p {
+        high += step;
+        step *= 2.0;
+    }
+    for _ in 0..200 {
+        let mid = 0.5 * (low + high);
+        if mid == low || mid == high {
+            break;
+        }
+        if cdf(mid)? < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(0.5 * (low + high))
+}
+
+fn invert_discrete(cdf: impl Fn(f64) -> Option<f64>, p: f64, upper: f64) -> Option<f64> {
+    // P(X ≤ k) ≥ p となる最小のk
+    if !(0.0..=1.0).contains(&p) {
+        return None;
+    }
+    let mut high = 1.0_f64;
+    while high < upper && cdf(high)? < p {
+        high *= 2.0;
+    }
+    let mut high = high.min(upper);
+    let mut low = -1.0;
+    while high - low > 1.0 {
+        let mid = ((low + high) / 2.0).floor();
+        if cdf(mid)? < p {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(high)
+}
+
+#[test]
+fn distrib_test() {
+    let close = |a: Option<f64>, b: f64| (a.unwrap() - b).abs() < 1e-9;
+    assert!(close(normal_pdf(0.0, 0.0, 1.0), 0.398_942_280_401_432_7));
+    assert!(close(normal_cdf(1.96, 0.0, 1.0), 0.975_002_104_851_779_5));
+    assert!(close(
+        normal_cdf(110.0, 100.0, 10.0),
+        0.841_344_746_068_542_9
+    ));
+    assert!(close(normal_inv(0.975, 0.0, 1.0), 1.959_963_984_540_054));
+    assert!(close(normal_inv(0.05, 50.0, 2.0), 46.710_292_746_097_04));
+    assert!(close(t_pdf(0.0, 1.0), 1.0 / f64::consts::PI));
+    assert!(close(t_cdf(2.0, 5.0), 0.949_030_260_585_070_6));
+    assert!(close(t_inv(0.975, 10.0), 2.228_138_851_986_274));
+    assert!(close(chi2_pdf(2.0, 2.0), 0.5 * (-1.0_f64).exp()));
+    assert!(close(chi2_cdf(3.841_458_820_694_124, 1.0), 0.95));
+    assert!(close(chi2_inv(0.95, 10.0), 18.307_038_053_275_146));
+    assert!(close(f_cdf(3.0, 5.0, 10.0), 0.934_442_437_906_116));
+    assert!(close(f_inv(0.95, 5.0, 10.0), 3.325_834_530_413_011));
+    assert!(close(f_pdf(1.0, 2.0, 2.0), 0.25));
+    // x = 0 の極限
+    assert!(close(chi2_pdf(0.0, 2.0), 0.5));
+    assert_eq!(chi2_pdf(0.0, 3.0), Some(0.0));
+    assert_eq!(chi2_pdf(0.0, 1.0), Some(f64::INFINITY));
+    assert!(close(f_pdf(0.0, 2.0, 7.0), 1.0));
+    assert_eq!(f_pdf(0.0, 5.0, 10.0), Some(0.0));
+    assert_eq!(f_pdf(0.0, 1.0, 10.0), Some(f64::INFINITY));
+    assert!(close(binomial_pmf(3.0, 10.0, 0.5), 120.0 / 1024.0));
+    assert!(close(binomial_cdf(3.0, 10.0, 0.5), 176.0 / 1024.0));
+    assert!(close(binomial_inv(0.5, 10.0, 0.5), 5.0));
+    assert!(close(poisson_pmf(2.0, 3.0), 4.5 * (-3.0_f64).exp()));
+    assert!(close(poisson_cdf(2.0, 3.0), 8.5 * (-3.0_f64).exp()));
+    assert!(close(poisson_inv(0.5, 3.0), 3.0));
+    assert!(normal_inv(1.0, 0.0, 1.0).is_none());
+    assert!(binomial_pmf(3.0, 10.0, 1.5).is_none());
+}
@@ -1,74 +1,196 @@
-pub fn future_value(n: f64, iyr: f64, pv: f64, pmt: f64) -> f64 {
+use crate::numeric;
+
+// 支払時期 (期末払い / 期首払い)
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PaymentMode {
+    #[default]
+    End,
+    Begin,
+}
+
+// 年間の支払回数(P/YR)と複利計算回数(C/YR)
+// 年利 iyr(%) から1期あたりの利率を (1 + iyr/100/C)^(C/P) - 1 で求める
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TvmConfig {
+    pub p_yr: f64,
+    pub c_yr: f64,
+    pub mode: PaymentMode,
+}
+
+impl Default for TvmConfig {
+    fn default() -> Self {
+        TvmConfig {
+            p_yr: 12.0,
+            c_yr: 12.0,
+            mode: PaymentMode::End,
+        }
+    }
+}
+
+impl TvmConfig {
+    pub fn periodic_rate(&self, iyr: f64) -> f64 {
+        match self.p_yr == self.c_yr {
+            true => iyr / 100.0 / self.p_yr,
+            false => (1.0 + iyr / 100.0 / self.c_yr).powf(self.c_yr / self.p_yr) - 1.0,
+        }
+    }
+
+    pub fn annual_rate(&self, i: f64) -> f64 {
+        // periodic_rateの逆
+        match self.p_yr == self.c_yr {
+            true => i * self.p_yr * 100.0,
+            false => ((1.0 + i).powf(self.p_yr / self.c_yr) - 1.0) * self.c_yr * 100.0,
+        }
+    }
+
+    fn annuity_factor(&self, i: f64) -> f64 {
+        // 期首払いは各支払いが1期分多く利息を生む
+        match self.mode {
+            PaymentMode::End => 1.0,
+            PaymentMode::Begin => 1.0 + i,
+        }
+    }
+}
+
+fn growth(i: f64, n: f64) -> f64 {
+    // (1 + i)^n
+    (n * i.ln_1p()).exp()
+}
+
+fn annuity(i: f64, n: f64) -> f64 {
+    // ((1 + i)^n - 1) / i  (i = 0 では n)
+    // 利率が0に近いときの桁落ちを避けるため exp_m1 を使う
+    match i == 0.0 {
+        true => n,
+        false => (n * i.ln_1p()).exp_m1() / i,
+    }
+}
+
+fn residual(n: f64, i: f64, pv: f64, pmt: f64, fv: f64, config: &TvmConfig) -> f64 {
+    // TVMの基本式 PV (1 + i)^n + PMT' ((1 + i)^n - 1) / i + FV = 0 の左辺
+    pv * growth(i, n) + pmt * config.annuity_factor(i) * annuity(i, n) + fv
+}
+
+pub fn future_value(n: f64, iyr: f64, pv: f64, pmt: f64, config: &TvmConfig) -> f64 {
     // 現在価値（PV, Present Value）、利率（IYR, Interest Rate per Year）
     // 各期の支払い額（PMT, Payment）、支払い回数（N, Number of Periods）
-    let i_yr = iyr / 100.0 / 12.0;
-    let fv = pv * (1.0 + i_yr).powf(n) + pmt * ((1.0 + i_yr).powf(n) - 1.0) / i_yr;
-    -fv
+    let i_yr = config.periodic_rate(iyr);
+    -residual(n, i_yr, pv, pmt, 0.0, config)
 }
 
-pub fn payment(n: f64, iyr: f64, pv: f64, fv: f64) -> f64 {
+pub fn payment(n: f64, iyr: f64, pv: f64, fv: f64, config: &TvmConfig) -> f64 {
     // 現在価値（PV, Present Value）、利率（IYR, Interest Rate per Year）
     // 未来価値（FV, Future Value）、支払い回数（N, Number of Periods）
-    let i_yr = iyr / 100.0 / 12.0;
-
-    (fv - pv * (1.0 + i_yr).powf(n)) / ((1.0 + i_yr).powf(n) - 1.0) * i_yr
+    let i_yr = config.periodic_rate(iyr);
+    -residual(n, i_yr, pv, 0.0, fv, config) / (config.annuity_factor(i_yr) * annuity(i_yr, n))
 }
-pub fn present_value(n: f64, iyr: f64, pmt: f64, fv: f64) -> f64 {
+pub fn present_value(n: f64, iyr: f64, pmt: f64, fv: f64, config: &TvmConfig) -> f64 {
     // 未来価値（FV, Future Value）、利率（IYR, Interest Rate per Year）
     // 各期の支払い額（PMT, Payment）、支払い回数（N, Number of Periods）
-    let i_yr = iyr / 100.0 / 12.0;
-    let pv = fv / ((1.0 + i_yr).powf(n)) + pmt * (1.0 - (1.0 + i_yr).powf(-n)) / i_yr;
-    -pv
+    let i_yr = config.periodic_rate(iyr);
+    -residual(n, i_yr, 0.0, pmt, fv, config) / growth(i_yr, n)
 }
 
-pub fn i_yr(n: f64, pv: f64, pmt: f64, fv: f64) -> Option<f64> {
-    // 年換算利率(I%RY)
-    // 二分探索法で力技で求める。
-    let mut upper = 100.0;
-    let mut low = 0.0;
-    let mut tyr = (upper + low) / 2.0;
-    let evo = |iyr| present_value(n, iyr, pmt, fv) - pv;
-    for _i in 0..100 {
-        tyr = (upper + low) / 2.0;
-        let ee = evo(tyr);
-        if ee > 0.0 {
-            if pv < 0.0 {
-                upper = tyr;
-            } else {
-                low = tyr;
-            }
-        } else if pv < 0.0 {
-            low = tyr;
-        } else {
-            upper = tyr
-        };
-        if ee.abs() < 0.000001 {
-            break;
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateReport {
+    // I/YR なら年利(%)、IRR なら1期あたりの利率(%)
+    pub rate: f64,
+    // 見つかった解の数 (符号変化の数)
+    pub roots: usize,
+    pub name: &'static str,
+}
+
+impl RateReport {
+    pub fn summary(&self) -> Option<String> {
+        // 解が複数あるときだけ注意を出す
+        match self.roots {
+            0 | 1 => None,
+            roots => Some(format!(
+                "{}: {roots} solutions (multiple sign changes), showing {:.6} nearest to 0",
+                self.name, self.rate
+            )),
         }
     }
+}
 
-    Some(tyr)
+// 1期あたり利率の探索範囲 ln(1 + i) ∈ [ln 0.01, ln 11] (-99% 〜 +1000%)
+const RATE_GRID: usize = 400;
+const LOG_RATE_MIN: f64 = -4.605_170_185_988_091;
+const LOG_RATE_MAX: f64 = 2.397_895_272_798_371;
+
+pub fn solve_rate<F>(f: F, name: &'static str) -> Result<(f64, usize), String>
+where
+    F: Fn(f64) -> f64,
+{
+    // f(i) = 0 となる1期あたりの利率 i を求める
+    // 格子上で走査して符号変化を探し、各区間をBrent法で解く
+    // 解が複数あるときは0に最も近いものと解の数を返す
+    let mut grid = (0..=RATE_GRID)
+        .map(|k| {
+            let u = LOG_RATE_MIN + (LOG_RATE_MAX - LOG_RATE_MIN) * k as f64 / RATE_GRID as f64;
+            u.exp_m1()
+        })
+        .chain([0.0])
+        .collect::<Vec<_>>();
+    grid.sort_by(f64::total_cmp);
+    let values = grid
+        .iter()
+        .map(|&i| (i, f(i)))
+        .filter(|(_, y)| y.is_finite())
+        .collect::<Vec<_>>();
+
+    let mut roots = values
+        .iter()
+        .filter(|(_, y)| *y == 0.0)
+        .map(|(i, _)| *i)
+        .collect::<Vec<_>>();
+    for pair in values.windows(2) {
+        let ((a, fa), (b, fb)) = (pair[0], pair[1]);
+        if fa != 0.0 && fb != 0.0 && fa.signum() != fb.signum() {
+            roots.push(numeric::brent(|i| Ok(f(i)), a, b)?.root);
+        }
+    }
+    let i = roots
+        .iter()
+        .copied()
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .ok_or(format!(
+            "{name}: no solution (check the signs of the cash flows)"
+        ))?;
+    Ok((i, roots.len()))
 }
 
-pub fn number_of_periods(iyr: f64, pv: f64, pmt: f64, fv: f64) -> f64 {
+pub fn i_yr(n: f64, pv: f64, pmt: f64, fv: f64, config: &TvmConfig) -> Result<RateReport, String> {
+    // 年換算利率(I%RY)
+    if n <= 0.0 {
+        return Err("I/YR needs n > 0".to_string());
+    }
+    let (i, roots) = solve_rate(|i| residual(n, i, pv, pmt, fv, config), "I/YR")?;
+    Ok(RateReport {
+        rate: config.annual_rate(i),
+        roots,
+        name: "I/YR",
+    })
+}
+
+pub fn number_of_periods(iyr: f64, pv: f64, pmt: f64, fv: f64, config: &TvmConfig) -> f64 {
     // 現在価値（PV, Present Value）、利率（IYR, Interest Rate per Year）
     // 未来価値（FV, Future Value）、各期の支払い額（PMT, Payment）
-    let i_yr = iyr / 100.0 / 12.0;
-    if pmt == 0.0 {
-        (fv / -pv).ln() / (1.0 + i_yr).ln()
-    } else if fv == 0.0 {
-        -((1.0 + (i_yr * pv) / pmt).ln() / (1.0 + i_yr).ln())
-    } else {
-        -(((pv + pmt.powf(1.0 + i_yr) / i_yr) / (fv - pmt.powf(1.0 + i_yr) / i_yr)).ln()
-            / (1.0 + i_yr).ln())
+    // (1 + i)^n = (PMT' - FV i) / (PMT' + PV i)  (PMT' は期首払いなら PMT (1 + i))
+    // 利率0では n = -(PV + FV) / PMT。解がなければNaN
+    let i_yr = config.periodic_rate(iyr);
+    let pmt = pmt * config.annuity_factor(i_yr);
+    match i_yr == 0.0 {
+        true => -(pv + fv) / pmt,
+        false => ((pmt - fv * i_yr) / (pmt + pv * i_yr)).ln() / i_yr.ln_1p(),
     }
 }
 
 #[allow(dead_code)]
-fn fv_loop(n: f64, iyr: f64, pv: f64, pmt: f64) -> f64 {
+fn fv_loop(n: f64, iyr: f64, pv: f64, pmt: f64, config: &TvmConfig) -> f64 {
     // 比較テスト用の関数
     // 愚直にループで計算
-    let i_yr = iyr / 100.0 / 12.0;
+    let i_yr = config.periodic_rate(iyr);
     let mut fv = pv;
     for _ in 0..(n as i32) {
         fv = fv * (1.0 + i_yr) + pmt;
@@ -78,20 +200,101 @@ fn fv_loop(n: f64, iyr: f64, pv: f64, pmt: f64) -> f64 {
 
 #[test]
 fn finance_test() {
-    assert!((fv_loop(12.0, 20.0, -22.0, -1.0) - 39.99).abs() < 0.01);
-    assert!((fv_loop(30.0, 10.0, 100.0, -2.0) - -60.4226).abs() < 0.01);
+    let m = TvmConfig::default();
+    assert!((fv_loop(12.0, 20.0, -22.0, -1.0, &m) - 39.99).abs() < 0.01);
+    assert!((fv_loop(30.0, 10.0, 100.0, -2.0, &m) - -60.4226).abs() < 0.01);
     assert!(
-        (fv_loop(30.0, 10.0, 100.0, -2.0) - future_value(30.0, 10.0, 100.0, -2.0)).abs() < 0.01
+        (fv_loop(30.0, 10.0, 100.0, -2.0, &m) - future_value(30.0, 10.0, 100.0, -2.0, &m)).abs()
+            < 0.01
     );
-    assert!((future_value(12.0, 20.0, -22.0, -1.0) - 39.99).abs() < 0.01);
-    assert!((payment(12.0, 20.0, 22.0, 0.0) + 2.038).abs() < 0.01);
-    assert!((future_value(30.0, 10.0, 100.0, -2.0) - -60.4226).abs() < 0.01);
-    assert!((present_value(12.0, 10.0, -1.0, 22.0) - -8.54017).abs() < 0.01);
-    assert!((present_value(24.0, 10.0, -1.0, 60.0) - -27.49372).abs() < 0.01);
-    assert!((present_value(6.0, 33.517, -5.5, 0.0) - 30.0).abs() < 0.01);
-    assert!((number_of_periods(10.0, 50.0, -1.0, 0.0) - 64.94871).abs() < 0.01);
-    assert!((number_of_periods(10.0, 20.0, 0.0, -25.0) - 26.88864).abs() < 0.01);
-    assert!((i_yr(6.0, -20.0, -1.0, 30.0).unwrap() - 33.287).abs() < 0.01);
-    assert!((i_yr(6.0, -20.0, 0.0, 30.0).unwrap() - 83.896).abs() < 0.01);
-    assert!((i_yr(6.0, 30.0, -5.5, 0.0).unwrap() - 33.517).abs() < 0.01);
+    assert!((future_value(12.0, 20.0, -22.0, -1.0, &m) - 39.99).abs() < 0.01);
+    assert!((payment(12.0, 20.0, 22.0, 0.0, &m) + 2.038).abs() < 0.01);
+    assert!((future_value(30.0, 10.0, 100.0, -2.0, &m) - -60.4226).abs() < 0.01);
+    assert!((present_value(12.0, 10.0, -1.0, 22.0, &m) - -8.54017).abs() < 0.01);
+    assert!((present_value(24.0, 10.0, -1.0, 60.0, &m) - -27.49372).abs() < 0.01);
+    assert!((present_value(6.0, 33.517, -5.5, 0.0, &m) - 30.0).abs() < 0.01);
+    assert!((number_of_periods(10.0, 50.0, -1.0, 0.0, &m) - 64.94871).abs() < 0.01);
+    assert!((number_of_periods(10.0, 20.0, 0.0, -25.0, &m) - 26.88864).abs() < 0.01);
+    assert!((i_yr(6.0, -20.0, -1.0, 30.0, &m).unwrap().rate - 33.287).abs() < 0.01);
+    assert!((i_yr(6.0, -20.0, 0.0, 30.0, &m).unwrap().rate - 83.896).abs() < 0.01);
+    assert!((i_yr(6.0, 30.0, -5.5, 0.0, &m).unwrap().rate - 33.517).abs() < 0.01);
+
+    // 年払い・年1回複利
+    let y = TvmConfig {
+        p_yr: 1.0,
+        c_yr: 1.0,
+        mode: PaymentMode::End,
+    };
+    assert!((future_value(10.0, 5.0, -100.0, 0.0, &y) - 162.889).abs() < 0.001);
+    assert!((i_yr(10.0, -100.0, 0.0, 162.889, &y).unwrap().rate - 5.0).abs() < 0.001);
+    // 月払い・半年複利 (カナダ式住宅ローン)
+    let q = TvmConfig {
+        p_yr: 12.0,
+        c_yr: 2.0,
+        mode: PaymentMode::End,
+    };
+    assert!((q.periodic_rate(6.0) - 0.004_938_622).abs() < 1e-9);
+    assert!((payment(300.0, 6.0, 100_000.0, 0.0, &q) + 639.81).abs() < 0.01);
+    let pmt = payment(300.0, 6.0, 100_000.0, 0.0, &q);
+    assert!((i_yr(300.0, 100_000.0, pmt, 0.0, &q).unwrap().rate - 6.0).abs() < 0.001);
+    assert!((number_of_periods(6.0, 100_000.0, pmt, 0.0, &q) - 300.0).abs() < 0.001);
+
+    // 期首払い: 10年間、年初に100ずつ積み立てる (年利5%)
+    let b = TvmConfig {
+        mode: PaymentMode::Begin,
+        ..y
+    };
+    assert!((future_value(10.0, 5.0, 0.0, -100.0, &b) - 1320.679).abs() < 0.001);
+    assert!((payment(10.0, 5.0, 0.0, 1320.679, &b) + 100.0).abs() < 0.001);
+    assert!((present_value(10.0, 5.0, -100.0, 0.0, &b) - 810.782).abs() < 0.001);
+    assert!((number_of_periods(5.0, 0.0, -100.0, 1320.679, &b) - 10.0).abs() < 0.001);
+    assert!((i_yr(10.0, 810.782, -100.0, 0.0, &b).unwrap().rate - 5.0).abs() < 0.001);
+
+    assert!((q.annual_rate(q.periodic_rate(6.0)) - 6.0).abs() < 1e-12);
+
+    // 負の利率・大きな利率
+    assert!((i_yr(1.0, -100.0, 0.0, 95.0, &y).unwrap().rate + 5.0).abs() < 1e-9);
+    assert!((i_yr(2.0, -100.0, 0.0, 900.0, &y).unwrap().rate - 200.0).abs() < 1e-9);
+    assert!((i_yr(12.0, -1000.0, 0.0, 500.0, &m).unwrap().rate + 67.351).abs() < 0.001);
+    // 利率0
+    assert_eq!(i_yr(10.0, 100.0, -10.0, 0.0, &m).unwrap().rate, 0.0);
+    assert_eq!(future_value(10.0, 0.0, -100.0, -10.0, &m), 200.0);
+    assert_eq!(payment(10.0, 0.0, 100.0, 0.0, &m), -10.0);
+    assert_eq!(present_value(10.0, 0.0, -10.0, 0.0, &m), 100.0);
+    assert_eq!(number_of_periods(0.0, 100.0, -10.0, 0.0, &m), 10.0);
+    assert!((present_value(10.0, 1e-12, -10.0, 0.0, &m) - 100.0).abs() < 1e-9);
+    // 解なし・複数解
+    assert!(i_yr(10.0, 100.0, 10.0, 0.0, &m).is_err());
+    assert!(i_yr(0.0, 100.0, -10.0, 0.0, &m).is_err());
+    let report = i_yr(2.0, -100.0, 230.0, -362.0, &y).unwrap();
+    assert_eq!(report.roots, 2);
+    assert!((report.rate - 10.0).abs() < 1e-9);
+    assert!(report.summary().is_some());
+    assert!(number_of_periods(10.0, 100.0, -0.5, 0.0, &m).is_nan());
+}
+
+#[test]
+fn tvm_round_trip_test() {
+    // PV と FV がともに0でないときの payment (FV の符号) と number_of_periods (一般式)
+    // future_value で求めた FV から PMT, N, PV を逆算して元に戻ること
+    let m = TvmConfig::default();
+    let fv = future_value(30.0, 10.0, 100.0, -2.0, &m);
+    assert!((fv + 60.4226).abs() < 1e-4);
+    assert!((payment(30.0, 10.0, 100.0, fv, &m) + 2.0).abs() < 1e-9);
+    assert!((number_of_periods(10.0, 100.0, -2.0, fv, &m) - 30.0).abs() < 1e-9);
+    assert!((present_value(30.0, 10.0, -2.0, fv, &m) - 100.0).abs() < 1e-9);
+    assert!((payment(30.0, 10.0, 100.0, -60.4226, &m) + 2.0).abs() < 1e-4);
+    assert!((number_of_periods(10.0, 100.0, -2.0, -60.4226, &m) - 30.0).abs() < 1e-4);
+
+    // 年払い: 手元の1000に毎年100を積み増す (年利5%, 期末・期首とも)
+    for mode in [PaymentMode::End, PaymentMode::Begin] {
+        let y = TvmConfig {
+            p_yr: 1.0,
+            c_yr: 1.0,
+            mode,
+        };
+        let fv = future_value(8.0, 5.0, -1000.0, -100.0, &y);
+        assert!((payment(8.0, 5.0, -1000.0, fv, &y) + 100.0).abs() < 1e-9);
+        assert!((number_of_periods(5.0, -1000.0, -100.0, fv, &y) - 8.0).abs() < 1e-9);
+    }
 }
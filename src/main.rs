@@ -1,6 +1,19 @@
+mod amort;
 mod calcrpn;
+mod cashflow;
+mod constants;
+mod currency;
+mod distrib;
 mod finance;
-use calcrpn::{CalcNum, DegMode, Memorize, manage_stack};
+mod numeric;
+mod numtheory;
+mod plot;
+mod poly;
+mod random;
+mod special;
+mod stats;
+mod symbolic;
+use calcrpn::{CalcNum, ComplexForm, DegMode, Memorize, Registers, TvmItem, manage_stack};
 use crossterm::execute;
 use ratatui::{
     layout::{Constraint, Direction, Layout},
@@ -24,6 +37,10 @@ fn run(terminal: &mut ratatui::DefaultTerminal) -> Result<(), Box<dyn std::error
     let mut memo_map: BTreeMap<String, CalcNum> = BTreeMap::new();
     let mut degmode = DegMode::Deg;
     let mut memo_mode: Option<Memorize> = None;
+    let mut registers = Registers {
+        rates: currency::RateTable::load(),
+        ..Registers::default()
+    };
     let mut result = String::new();
     let mut memory = String::new();
     let mut message = String::new();
@@ -63,8 +80,32 @@ fn run(terminal: &mut ratatui::DefaultTerminal) -> Result<(), Box<dyn std::error
                     .split(f.area());
                 // ステータスバー
                 let status_block = Block::default().borders(Borders::NONE);
+                let modulus = match registers.modulus {
+                    Some(m) => format!(" Mod:{m}"),
+                    None => String::new(),
+                };
+                let complex_form = match registers.complex_form {
+                    ComplexForm::Polar => " Polar",
+                    ComplexForm::Rect => "",
+                };
+                let fit_model = match &registers.fit {
+                    Some(fit) => format!(" | Fit:{:?}", fit.model),
+                    None => String::new(),
+                };
+                let equation = match &registers.equation {
+                    Some(eq) => format!(" | EQ:\"{eq}\""),
+                    None => String::new(),
+                };
+                let tvm = match TvmItem::config(&memo_map, registers.payment_mode) {
+                    Some(config) => format!(
+                        " | P/YR:{} C/YR:{} {:?}",
+                        config.p_yr, config.c_yr, config.mode
+                    ),
+                    None => " | P/YR,C/YR Error".to_string(),
+                };
                 let status_text = Paragraph::new(format!(
-                    "Fix:{decimal_point} | {degmode:?} | MemoMode:{memo_mode:?}",
+                    "Fix:{decimal_point} | {degmode:?}{complex_form}{modulus} | MemoMode:{memo_mode:?} | Σn:{}{tvm}{fit_model}{equation}",
+                    registers.stat.len(),
                 ))
                 .block(status_block);
 
@@ -115,6 +156,16 @@ fn run(terminal: &mut ratatui::DefaultTerminal) -> Result<(), Box<dyn std::error
                 input_log.push_back(input.clone());
             }
 
+            // 定数の一覧 (const の後ろは検索語)
+            if let Some(query) = input.trim().strip_prefix("const")
+                && (query.is_empty() || query.starts_with(' '))
+            {
+                match constants::run_browser(terminal, query.trim())? {
+                    Some(constant) => input = format!("_{}", constant.symbol),
+                    None => continue,
+                }
+            }
+
             match input.trim() {
                 "undo" => {
                     // undoの処理
@@ -122,7 +173,7 @@ fn run(terminal: &mut ratatui::DefaultTerminal) -> Result<(), Box<dyn std::error
                     if let Some((st, rst)) = last_stackresult.pop_back() {
                         stack = st;
                         result = rst;
-                        update_stack(&stack, &mut result, decimal_point);
+                        update_stack(&stack, &mut result, decimal_point, &registers, &degmode);
                     };
                     message = "Undo".to_string();
                     last_stackresult.push_back((stack.clone(), result.clone()));
@@ -139,6 +190,47 @@ fn run(terminal: &mut ratatui::DefaultTerminal) -> Result<(), Box<dyn std::error
                     input_log.clear();
                     terminal.clear()?;
                 }
+                "cf" | "cfedit" => {
+                    // キャッシュフロー表の編集
+                    cashflow::run_editor(terminal, &mut registers.cashflow)?;
+                    continue;
+                }
+                "rate" | "rates" => {
+                    message = registers.rates.summary();
+                    continue;
+                }
+                rate if rate.starts_with("rate ") => {
+                    // 'rate usd jpy 150.2' でレートを設定し、設定ディレクトリに保存する
+                    let args = rate.split_whitespace().skip(1).collect::<Vec<_>>();
+                    message = match args[..] {
+                        [from, to, value] => match (
+                            currency::code(from),
+                            currency::code(to),
+                            value.parse::<f64>(),
+                        ) {
+                            (Some(from), Some(to), Ok(value))
+                                if value > 0.0 && value.is_finite() && from != to =>
+                            {
+                                let date = currency::today();
+                                registers.rates.set(from, to, value, &date);
+                                match registers.rates.save() {
+                                    Ok(()) => format!("Rate: 1 {from} = {value} {to} ({date})"),
+                                    Err(e) => format!("Error: rate not saved: {e}"),
+                                }
+                            }
+                            _ => "Error: rate usd jpy 150.2".to_string(),
+                        },
+                        [from, to] => match (currency::code(from), currency::code(to)) {
+                            (Some(from), Some(to)) => match registers.rates.lookup(from, to) {
+                                Some((value, date)) => format!("1 {from} = {value} {to} ({date})"),
+                                None => format!("Error: No rate for {from}/{to}"),
+                            },
+                            _ => "Error: Unknown currency".to_string(),
+                        },
+                        _ => "Error: rate usd jpy 150.2".to_string(),
+                    };
+                    continue;
+                }
                 _ => {
                     let app_command = input.split_whitespace().collect::<Vec<&str>>();
                     if app_command.len() == 2 {
@@ -153,7 +245,7 @@ fn run(terminal: &mut ratatui::DefaultTerminal) -> Result<(), Box<dyn std::error
                                 // 特定のmemo keyを削除
                                 memo_map.remove_entry(app_command[1]);
                                 update_log(&mut input_log, &mut message);
-                                update_memo(&memo_map, &mut memory);
+                                update_memo(&memo_map, &mut memory, &registers, &degmode);
                                 terminal.clear()?;
                             }
                             _ => (),
@@ -167,11 +259,35 @@ fn run(terminal: &mut ratatui::DefaultTerminal) -> Result<(), Box<dyn std::error
                         &mut degmode,
                         &mut memo_map,
                         &mut memo_mode,
+                        &mut registers,
                     ) {
                         Ok(()) => {
                             // 入力を履歴に追加
                             readline.add_history_entry(&input)?;
                             update_log(&mut input_log, &mut message);
+                            if let Some(notice) = registers.notice.take() {
+                                message = notice;
+                            }
+                            if let Some(schedule) = registers.schedule.take()
+                                && let Some(saved) = amort::run_table(terminal, &schedule)?
+                            {
+                                message = saved;
+                            }
+                            if let Some((a, b)) = registers.plot.take()
+                                && let Some(program) = registers.equation.clone()
+                            {
+                                let mut view = plot::PlotView::new(a, b);
+                                plot::run_plot(terminal, &program, &mut view, |x| {
+                                    calcrpn::eval_program(
+                                        &program,
+                                        &CalcNum::Number(x),
+                                        &degmode,
+                                        &memo_map,
+                                    )
+                                    .ok()
+                                    .and_then(|y| y.get_realnumber().ok())
+                                })?;
+                            }
                             last_stackresult.push_back((stack.clone(), result.clone()));
                             if last_stackresult.len() > 4 {
                                 last_stackresult.pop_front();
@@ -187,8 +303,8 @@ fn run(terminal: &mut ratatui::DefaultTerminal) -> Result<(), Box<dyn std::error
                     }
                     result.clear();
                     memory.clear();
-                    update_stack(&stack, &mut result, decimal_point);
-                    update_memo(&memo_map, &mut memory);
+                    update_stack(&stack, &mut result, decimal_point, &registers, &degmode);
+                    update_memo(&memo_map, &mut memory, &registers, &degmode);
                 }
             }
         }
@@ -224,14 +340,29 @@ fn update_log(input_log: &mut VecDeque<String>, message: &mut String) {
         .fold("Hist: ".to_string(), |acc, x| acc + x + " → ");
 }
 
-fn update_memo(memo_map: &BTreeMap<String, CalcNum>, memory: &mut String) {
+fn update_memo(
+    memo_map: &BTreeMap<String, CalcNum>,
+    memory: &mut String,
+    registers: &Registers,
+    degmode: &DegMode,
+) {
     // memo_mapをStringに変換しスペースで区切る
     for (key, sval) in memo_map.iter() {
-        memory.push_str(&format!("{} -> {} ", key, sval.num_format(2)));
+        memory.push_str(&format!(
+            "{} -> {} ",
+            key,
+            sval.display(2, registers.complex_form, degmode)
+        ));
     }
 }
 
-fn update_stack(stack: &VecDeque<CalcNum>, result: &mut String, decimal_point: usize) {
+fn update_stack(
+    stack: &VecDeque<CalcNum>,
+    result: &mut String,
+    decimal_point: usize,
+    registers: &Registers,
+    degmode: &DegMode,
+) {
     // 改行で区切る。windowsの場合は\r\n, Mac|linuxの場合は\n
     let sepalator = if cfg!(target_os = "windows") {
         "\r\n"
@@ -243,7 +374,7 @@ fn update_stack(stack: &VecDeque<CalcNum>, result: &mut String, decimal_point: u
         .rev()
         .take(10)
         .rev()
-        .map(|x| x.num_format(decimal_point))
+        .map(|x| x.display(decimal_point, registers.complex_form, degmode))
         .collect::<Vec<_>>()
         .join(sepalator);
 }
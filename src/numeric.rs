@@ -0,0 +1,392 @@
+use num::complex::Complex;
+
+// 数値計算(求根・積分・微分)
+// 関数の評価はユーザーのプログラムを実行するため失敗し得る。そのため Result を返す関数を受け取る。
+
+const MAX_ITER: usize = 200;
+
+// 求根の結果と収束状況
+#[derive(Debug, Clone)]
+pub struct RootReport {
+    pub root: f64,
+    pub value: f64,
+    pub iterations: usize,
+    pub method: &'static str,
+}
+
+impl RootReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: x = {} f(x) = {:.3e} ({} iterations)",
+            self.method, self.root, self.value, self.iterations
+        )
+    }
+}
+
+pub fn brent<F>(mut f: F, a: f64, b: f64) -> Result<RootReport, String>
+where
+    F: FnMut(f64) -> Result<f64, String>,
+{
+    // Brent法 (二分法・割線法・逆二次補間の組み合わせ)
+    // [a, b] で符号が変わっている必要がある
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a)?, f(b)?);
+    if fa == 0.0 {
+        return Ok(report(a, fa, 0, "Brent"));
+    }
+    if fb == 0.0 {
+        return Ok(report(b, fb, 0, "Brent"));
+    }
+    if fa.signum() == fb.signum() {
+        return Err("No sign change in bracket".to_string());
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+    for iteration in 1..=MAX_ITER {
+        if fb.signum() == fc.signum() {
+            (c, fc) = (a, fa);
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            (a, b, c) = (b, c, b);
+            (fa, fb, fc) = (fb, fc, fb);
+        }
+        let tol = 2.0 * f64::EPSILON * b.abs() + 1e-300;
+        let m = 0.5 * (c - b);
+        if m.abs() <= tol || fb == 0.0 {
+            return Ok(report(b, fb, iteration, "Brent"));
+        }
+        if e.abs() >= tol && fa.abs() > fb.abs() {
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * m * s, 1.0 - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * m * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * m * q - (tol * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = m;
+                e = m;
+            }
+        } else {
+            d = m;
+            e = m;
+        }
+        (a, fa) = (b, fb);
+        b += if d.abs() > tol { d } else { tol.copysign(m) };
+        fb = f(b)?;
+    }
+    Err(format!("Not converged after {MAX_ITER} iterations"))
+}
+
+pub fn solve<F>(mut f: F, guess: f64) -> Result<RootReport, String>
+where
+    F: FnMut(f64) -> Result<f64, String>,
+{
+    // 初期値からの求根
+    // 割線法で探し、符号変化が見つかればBrent法で確実に収束させる
+    let mut x0 = guess;
+    let mut x1 = if guess == 0.0 {
+        1e-4
+    } else {
+        guess * (1.0 + 1e-4)
+    };
+    let (mut f0, mut f1) = (f(x0)?, f(x1)?);
+    for iteration in 1..=MAX_ITER {
+        if f1 == 0.0 {
+            return Ok(report(x1, f1, iteration, "Secant"));
+        }
+        if f0.signum() != f1.signum() {
+            return brent(&mut f, x0, x1).map(|r| RootReport {
+                iterations: r.iterations + iteration,
+                ..r
+            });
+        }
+        if f1 == f0 {
+            break;
+        }
+        let x2 = x1 - f1 * (x1 - x0) / (f1 - f0);
+        if !x2.is_finite() {
+            break;
+        }
+        let f2 = f(x2)?;
+        if (x2 - x1).abs() <= 4.0 * f64::EPSILON * x2.abs().max(1e-300) {
+            return Ok(report(x2, f2, iteration, "Secant"));
+        }
+        (x0, f0, x1, f1) = (x1, f1, x2, f2);
+    }
+    // 割線法で見つからない場合は初期値から区間を広げて符号変化を探す
+    let fg = f(guess)?;
+    let mut step = guess.abs().max(1.0) * 1e-2;
+    for _ in 0..60 {
+        for x in [guess - step, guess + step] {
+            let fx = f(x)?;
+            if fx.is_finite() && fx.signum() != fg.signum() {
+                let (a, b) = if x < guess { (x, guess) } else { (guess, x) };
+                return brent(&mut f, a, b);
+            }
+        }
+        step *= 2.0;
+    }
+    Err("No root found near guess".to_string())
+}
+
+// 積分の結果と誤差の見積もり
+#[derive(Debug, Clone)]
+pub struct IntegralReport {
+    pub value: f64,
+    pub error: f64,
+    pub evaluations: usize,
+}
+
+impl IntegralReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "G7K15: ∫ = {} ± {:.3e} ({} evaluations)",
+            self.value, self.error, self.evaluations
+        )
+    }
+}
+
+// Gauss-Kronrod (7点Gauss / 15点Kronrod) の節点と重み (正の節点のみ、最後が中点)
+const KRONROD_NODES: [f64; 8] = [
+    0.991_455_371_120_813,
+    0.949_107_912_342_759,
+    0.864_864_423_359_769,
+    0.741_531_185_599_394,
+    0.586_087_235_467_691,
+    0.405_845_151_377_397,
+    0.207_784_955_007_898,
+    0.0,
+];
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022_935_322_010_529,
+    0.063_092_092_629_979,
+    0.104_790_010_322_250,
+    0.140_653_259_715_525,
+    0.169_004_726_639_267,
+    0.190_350_578_064_785,
+    0.204_432_940_075_298,
+    0.209_482_141_084_728,
+];
+// Gauss 7点の重み (Kronrodの節点の奇数番目に対応)
+const GAUSS_WEIGHTS: [f64; 4] = [
+    0.129_484_966_168_870,
+    0.279_705_391_489_277,
+    0.381_830_050_505_119,
+    0.417_959_183_673_469,
+];
+const MAX_SEGMENTS: usize = 500;
+
+fn gauss_kronrod<F>(f: &mut F, a: f64, b: f64) -> Result<(f64, f64), String>
+where
+    F: FnMut(f64) -> Result<f64, String>,
+{
+    // 区間 [a, b] での15点Kronrodの値と7点Gaussとの差
+    let center = 0.5 * (a + b);
+    let half = 0.5 * (b - a);
+    let mid = f(center)?;
+    let mut kronrod = mid * KRONROD_WEIGHTS[7];
+    let mut gauss = mid * GAUSS_WEIGHTS[3];
+    for i in 0..7 {
+        let dx = half * KRONROD_NODES[i];
+        let pair = f(center - dx)? + f(center + dx)?;
+        kronrod += KRONROD_WEIGHTS[i] * pair;
+        if i % 2 == 1 {
+            gauss += GAUSS_WEIGHTS[i / 2] * pair;
+        }
+    }
+    Ok((kronrod * half, ((kronrod - gauss) * half).abs()))
+}
+
+pub fn integrate<F>(mut f: F, a: f64, b: f64, tolerance: f64) -> Result<IntegralReport, String>
+where
+    F: FnMut(f64) -> Result<f64, String>,
+{
+    // 適応型Gauss-Kronrod積分
+    // 誤差の最も大きい区間を二分していき、誤差の合計が許容値を下回れば終了する
+    if a == b {
+        return Ok(IntegralReport {
+            value: 0.0,
+            error: 0.0,
+            evaluations: 0,
+        });
+    }
+    let (value, error) = gauss_kronrod(&mut f, a, b)?;
+    let mut segments = vec![(a, b, value, error)];
+    let mut evaluations = 15;
+    loop {
+        let value = segments.iter().map(|s| s.2).sum::<f64>();
+        let error = segments.iter().map(|s| s.3).sum::<f64>();
+        if !value.is_finite() {
+            return Err("Integral diverges".to_string());
+        }
+        if error <= tolerance.max(tolerance * value.abs()) || segments.len() >= MAX_SEGMENTS {
+            if segments.len() >= MAX_SEGMENTS && error > 1e-3 * value.abs().max(1.0) {
+                return Err(format!("Not converged (error {error:.3e})"));
+            }
+            return Ok(IntegralReport {
+                value,
+                error,
+                evaluations,
+            });
+        }
+        let worst = segments
+            .iter()
+            .enumerate()
+            .max_by(|x, y| x.1.3.total_cmp(&y.1.3))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let (left, right, _, _) = segments.swap_remove(worst);
+        let center = 0.5 * (left + right);
+        for (l, r) in [(left, center), (center, right)] {
+            let (v, e) = gauss_kronrod(&mut f, l, r)?;
+            segments.push((l, r, v, e));
+        }
+        evaluations += 30;
+    }
+}
+
+// 微分の結果と誤差の見積もり
+#[derive(Debug, Clone)]
+pub struct DerivativeReport {
+    pub value: f64,
+    pub error: f64,
+    pub method: &'static str,
+}
+
+impl DerivativeReport {
+    pub fn summary(&self) -> String {
+        format!("{}: d = {} ± {:.3e}", self.method, self.value, self.error)
+    }
+}
+
+pub fn derivative<F>(mut f: F, x: f64, order: u8) -> Result<DerivativeReport, String>
+where
+    F: FnMut(f64) -> Result<f64, String>,
+{
+    // 中心差分をRichardson補外する (Ridders法)
+    // 刻み幅を1/1.4ずつ小さくし、誤差の最も小さい補外値を採用する
+    const SHRINK: f64 = 1.4;
+    const TABLE: usize = 10;
+    let fx = if order == 2 { f(x)? } else { 0.0 };
+    let mut difference = |h: f64| -> Result<f64, String> {
+        match order {
+            1 => Ok((f(x + h)? - f(x - h)?) / (2.0 * h)),
+            2 => Ok((f(x + h)? - 2.0 * fx + f(x - h)?) / (h * h)),
+            _ => Err("Only 1st and 2nd derivatives are supported".to_string()),
+        }
+    };
+    let mut h = 0.1 * x.abs().max(1.0);
+    let mut table = vec![vec![0.0; TABLE]; TABLE];
+    table[0][0] = difference(h)?;
+    let mut best = table[0][0];
+    let mut error = f64::INFINITY;
+    for i in 1..TABLE {
+        h /= SHRINK;
+        table[0][i] = difference(h)?;
+        let mut factor = SHRINK * SHRINK;
+        for j in 1..=i {
+            table[j][i] = (table[j - 1][i] * factor - table[j - 1][i - 1]) / (factor - 1.0);
+            factor *= SHRINK * SHRINK;
+            let estimate = (table[j][i] - table[j - 1][i])
+                .abs()
+                .max((table[j][i] - table[j - 1][i - 1]).abs());
+            if estimate <= error {
+                error = estimate;
+                best = table[j][i];
+            }
+        }
+        // 丸め誤差で補外が悪化し始めたら打ち切る
+        if (table[i][i] - table[i - 1][i - 1]).abs() >= 2.0 * error {
+            break;
+        }
+    }
+    if !best.is_finite() {
+        return Err("Derivative is not finite".to_string());
+    }
+    Ok(DerivativeReport {
+        value: best,
+        error,
+        method: "Richardson",
+    })
+}
+
+pub fn complex_step<F>(mut f: F, x: f64) -> Result<DerivativeReport, String>
+where
+    F: FnMut(Complex<f64>) -> Result<Complex<f64>, String>,
+{
+    // 複素ステップ微分 f'(x) ≈ Im f(x + ih) / h
+    // 差をとらないので桁落ちがなく、解析的な関数ならほぼ機械精度で求まる
+    let h = 1e-20 * x.abs().max(1.0);
+    let value = f(Complex::new(x, h))?.im / h;
+    if !value.is_finite() {
+        return Err("Derivative is not finite".to_string());
+    }
+    Ok(DerivativeReport {
+        value,
+        error: f64::EPSILON * value.abs(),
+        method: "Complex step",
+    })
+}
+
+fn report(root: f64, value: f64, iterations: usize, method: &'static str) -> RootReport {
+    RootReport {
+        root,
+        value,
+        iterations,
+        method,
+    }
+}
+
+#[test]
+fn numeric_test() {
+    let sqrt2 = brent(|x| Ok(x * x - 2.0), 0.0, 2.0).unwrap();
+    assert!((sqrt2.root - 2.0_f64.sqrt()).abs() < 1e-15);
+    assert!(brent(|x| Ok(x * x + 1.0), -1.0, 2.0).is_err());
+
+    let cube = solve(|x| Ok(x * x * x - 8.0), 1.0).unwrap();
+    assert!((cube.root - 2.0).abs() < 1e-14);
+    let cos = solve(|x: f64| Ok(x.cos() - x), 0.0).unwrap();
+    assert!((cos.root - 0.739_085_133_215_160_6).abs() < 1e-15);
+    let far = solve(|x| Ok(x - 1e6), -3.0).unwrap();
+    assert!((far.root - 1e6).abs() < 1e-8);
+    assert!(solve(|x| Ok(x * x + 1.0), 0.5).is_err());
+    assert!(solve(|_| Err("bad".to_string()), 0.5).is_err());
+
+    let sine = integrate(|x: f64| Ok(x.sin()), 0.0, std::f64::consts::PI, 1e-12).unwrap();
+    assert!((sine.value - 2.0).abs() < 1e-13);
+    assert!(sine.error <= 2e-12);
+    let gauss = integrate(|x: f64| Ok((-x * x).exp()), -10.0, 10.0, 1e-12).unwrap();
+    assert!((gauss.value - std::f64::consts::PI.sqrt()).abs() < 1e-12);
+    let root = integrate(|x: f64| Ok(x.sqrt()), 0.0, 1.0, 1e-10).unwrap();
+    assert!((root.value - 2.0 / 3.0).abs() < 1e-10);
+    let reversed = integrate(|x| Ok(x * x), 3.0, 0.0, 1e-12).unwrap();
+    assert!((reversed.value + 9.0).abs() < 1e-13);
+    assert!(integrate(|x| Ok(1.0 / x), 0.0, 1.0, 1e-12).is_err());
+
+    let exp = derivative(|x: f64| Ok(x.exp()), 1.0, 1).unwrap();
+    assert!((exp.value - 1.0_f64.exp()).abs() < 1e-11);
+    let sin = derivative(|x: f64| Ok(x.sin()), 0.5, 2).unwrap();
+    assert!((sin.value + 0.5_f64.sin()).abs() < 1e-8);
+    let big = derivative(|x: f64| Ok(x.ln()), 1e6, 1).unwrap();
+    assert!((big.value - 1e-6).abs() < 1e-16);
+    assert!(derivative(|x| Ok(x * x), 0.0, 3).is_err());
+    let step = complex_step(|z: Complex<f64>| Ok(z.exp() / z.sin()), 1.5).unwrap();
+    let expected = 1.5_f64.exp() * (1.5_f64.sin() - 1.5_f64.cos()) / 1.5_f64.sin().powi(2);
+    assert!((step.value - expected).abs() < 1e-14);
+}
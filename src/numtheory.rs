@@ -0,0 +1,197 @@
+// 整数論の関数
+// f64を経由せず、u64/u128の整数で正確に計算する。
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+pub fn pow_mod(base: u64, exp: u64, m: u64) -> Option<u64> {
+    // 繰り返し二乗法
+    if m == 0 {
+        return None;
+    }
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut exp = exp;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    Some(result)
+}
+
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    // 拡張ユークリッドの互除法
+    if m == 0 {
+        return None;
+    }
+    let (mut old_r, mut r) = (a as i128 % m as i128, m as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+    }
+    if old_r != 1 {
+        return None;
+    }
+    Some(old_s.rem_euclid(m as i128) as u64)
+}
+
+pub fn is_prime(n: u64) -> bool {
+    // ミラー・ラビン素数判定 (u64の範囲では以下の基数で決定的)
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for p in BASES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    BASES.iter().all(|&a| {
+        let mut x = pow_mod(a, d, n).unwrap_or(0);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
+pub fn next_prime(n: u64) -> Option<u64> {
+    // nより大きい最小の素数
+    let mut candidate = n.checked_add(1)?;
+    while !is_prime(candidate) {
+        candidate = candidate.checked_add(1)?;
+    }
+    Some(candidate)
+}
+
+fn pollard_rho(n: u64) -> u64 {
+    // ポラードのρ法で非自明な約数を一つ探す (nは合成数)
+    if n.is_multiple_of(2) {
+        return 2;
+    }
+    for c in 1u64.. {
+        let f = |x: u64| ((mul_mod(x, x, n) as u128 + c as u128) % n as u128) as u64;
+        let (mut x, mut y, mut d) = (2u64, 2u64, 1u64);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd(x.abs_diff(y), n);
+        }
+        if d != n {
+            return d;
+        }
+    }
+    n
+}
+
+pub fn factorize(n: u64) -> Vec<(u64, u32)> {
+    // 素因数分解 [(素数, 指数)]
+    let mut primes = Vec::new();
+    let mut rest = n;
+    for p in [2u64, 3, 5, 7, 11, 13] {
+        while rest.is_multiple_of(p) && rest > 1 {
+            primes.push(p);
+            rest /= p;
+        }
+    }
+    let mut stack = vec![rest];
+    while let Some(m) = stack.pop() {
+        if m <= 1 {
+            continue;
+        }
+        if is_prime(m) {
+            primes.push(m);
+        } else {
+            let d = pollard_rho(m);
+            stack.push(d);
+            stack.push(m / d);
+        }
+    }
+    primes.sort_unstable();
+    let mut factors: Vec<(u64, u32)> = Vec::new();
+    for p in primes {
+        match factors.last_mut() {
+            Some((last, exp)) if *last == p => *exp += 1,
+            _ => factors.push((p, 1)),
+        }
+    }
+    factors
+}
+
+pub fn totient(n: u64) -> u64 {
+    // オイラーのφ関数
+    factorize(n).iter().fold(n, |acc, (p, _)| acc / p * (p - 1))
+}
+
+pub fn format_factors(factors: &[(u64, u32)]) -> String {
+    // [(2, 3), (3, 1), (5, 1)] -> "2^3·3·5"
+    factors
+        .iter()
+        .map(|(p, e)| match e {
+            1 => p.to_string(),
+            _ => format!("{p}^{e}"),
+        })
+        .collect::<Vec<_>>()
+        .join("·")
+}
+
+#[test]
+fn numtheory_test() {
+    assert_eq!(gcd(48, 18), 6);
+    assert_eq!(gcd(0, 7), 7);
+    assert_eq!(lcm(4, 6), Some(12));
+    assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+    assert_eq!(pow_mod(4, 13, 497), Some(445));
+    assert_eq!(pow_mod(2, 10, 1), Some(0));
+    assert_eq!(
+        pow_mod(9_007_199_254_740_881, 2, 9_007_199_254_740_997),
+        Some(13_456)
+    );
+    assert_eq!(mod_inverse(3, 11), Some(4));
+    assert_eq!(mod_inverse(6, 9), None);
+    assert!(is_prime(2));
+    assert!(is_prime(9_007_199_254_740_881));
+    assert!(!is_prime(1));
+    assert!(!is_prime(561));
+    assert!(!is_prime(3_215_031_751));
+    assert_eq!(next_prime(13), Some(17));
+    assert_eq!(next_prime(0), Some(2));
+    assert_eq!(factorize(120), vec![(2, 3), (3, 1), (5, 1)]);
+    assert_eq!(
+        factorize(9_007_199_254_740_991),
+        vec![(6_361, 1), (69_431, 1), (20_394_401, 1)]
+    );
+    assert_eq!(factorize(1), vec![]);
+    assert_eq!(totient(36), 12);
+    assert_eq!(totient(97), 96);
+    assert_eq!(format_factors(&factorize(120)), "2^3·3·5");
+}
@@ -0,0 +1,202 @@
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Style},
+    symbols::Marker,
+    text::Line,
+    widgets::{Axis, Block, Borders, Chart, Clear, Dataset, GraphType},
+};
+
+// グラフ表示
+// 登録した式を区間 [x_min, x_max] で標本化し、ポップアップで描画する。
+// h/l: 左右に移動, +/-: 拡大/縮小, ←/→: トレースカーソル, 0: 元の範囲, q/Esc: 閉じる
+
+const SAMPLES: usize = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotView {
+    pub x_min: f64,
+    pub x_max: f64,
+    // トレースカーソルの標本番号
+    pub cursor: usize,
+}
+
+impl PlotView {
+    pub fn new(a: f64, b: f64) -> Self {
+        let (x_min, x_max) = if a <= b { (a, b) } else { (b, a) };
+        PlotView {
+            x_min,
+            x_max,
+            cursor: SAMPLES / 2,
+        }
+    }
+
+    pub fn x_at(&self, index: usize) -> f64 {
+        self.x_min + (self.x_max - self.x_min) * index as f64 / SAMPLES as f64
+    }
+
+    pub fn pan(&mut self, ratio: f64) {
+        // 表示幅のratio倍だけ移動する
+        let shift = (self.x_max - self.x_min) * ratio;
+        self.x_min += shift;
+        self.x_max += shift;
+    }
+
+    pub fn zoom(&mut self, factor: f64) {
+        // カーソル位置を中心に表示幅をfactor倍にする
+        let center = self.x_at(self.cursor);
+        self.x_min = center - (center - self.x_min) * factor;
+        self.x_max = center + (self.x_max - center) * factor;
+    }
+
+    pub fn move_cursor(&mut self, step: isize) {
+        self.cursor = self.cursor.saturating_add_signed(step).min(SAMPLES);
+    }
+}
+
+pub fn sample<F>(view: &PlotView, mut f: F) -> Vec<Option<f64>>
+where
+    F: FnMut(f64) -> Option<f64>,
+{
+    // 評価できない点や有限でない点はNone
+    (0..=SAMPLES)
+        .map(|i| f(view.x_at(i)).filter(|y| y.is_finite()))
+        .collect()
+}
+
+pub fn y_bounds(values: &[Option<f64>]) -> [f64; 2] {
+    // 有限値の最小・最大に少し余白を加える。定数の場合は±1
+    let (low, high) = values
+        .iter()
+        .flatten()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), y| {
+            (lo.min(*y), hi.max(*y))
+        });
+    if !low.is_finite() {
+        return [-1.0, 1.0];
+    }
+    if low == high {
+        return [low - 1.0, high + 1.0];
+    }
+    let margin = (high - low) * 0.05;
+    [low - margin, high + margin]
+}
+
+fn popup_area(area: Rect) -> Rect {
+    let [area] = Layout::vertical([Constraint::Percentage(80)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(90)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
+pub fn run_plot<F>(
+    terminal: &mut ratatui::DefaultTerminal,
+    title: &str,
+    view: &mut PlotView,
+    mut f: F,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: FnMut(f64) -> Option<f64>,
+{
+    let home = view.clone();
+    loop {
+        let values = sample(view, &mut f);
+        let points = values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, y)| y.map(|y| (view.x_at(i), y)))
+            .collect::<Vec<_>>();
+        let [y_min, y_max] = y_bounds(&values);
+        let cursor_x = view.x_at(view.cursor);
+        let cursor_y = values[view.cursor];
+        let cursor_line = [(cursor_x, y_min), (cursor_x, y_max)];
+        let trace = match cursor_y {
+            Some(y) => format!(" x = {cursor_x:.6} y = {y:.6} "),
+            None => format!(" x = {cursor_x:.6} y = undefined "),
+        };
+
+        terminal.draw(|frame| {
+            let area = popup_area(frame.area());
+            let datasets = vec![
+                Dataset::default()
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Scatter)
+                    .style(Style::default().fg(Color::DarkGray))
+                    .data(&cursor_line),
+                Dataset::default()
+                    .marker(Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(Color::Cyan))
+                    .data(&points),
+            ];
+            let axis_labels = |low: f64, high: f64| {
+                [low, (low + high) / 2.0, high]
+                    .map(|v| Line::from(format!("{v:.3}")))
+                    .to_vec()
+            };
+            let chart = Chart::new(datasets)
+                .block(
+                    Block::default()
+                        .title(format!(" Plot \"{title}\" "))
+                        .title_bottom(trace.clone())
+                        .title_bottom(" h/l:Pan +/-:Zoom ←/→:Trace 0:Reset q:Close ")
+                        .borders(Borders::ALL),
+                )
+                .x_axis(
+                    Axis::default()
+                        .bounds([view.x_min, view.x_max])
+                        .labels(axis_labels(view.x_min, view.x_max)),
+                )
+                .y_axis(
+                    Axis::default()
+                        .bounds([y_min, y_max])
+                        .labels(axis_labels(y_min, y_max)),
+                );
+            frame.render_widget(Clear, area);
+            frame.render_widget(chart, area);
+        })?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('h') => view.pan(-0.25),
+                KeyCode::Char('l') => view.pan(0.25),
+                KeyCode::Char('+') | KeyCode::Char('=') => view.zoom(0.5),
+                KeyCode::Char('-') => view.zoom(2.0),
+                KeyCode::Char('0') => *view = home.clone(),
+                KeyCode::Left => view.move_cursor(-1),
+                KeyCode::Right => view.move_cursor(1),
+                _ => (),
+            }
+        }
+    }
+    terminal.clear()?;
+    Ok(())
+}
+
+#[test]
+fn plot_test() {
+    let mut view = PlotView::new(2.0, -2.0);
+    assert_eq!((view.x_min, view.x_max), (-2.0, 2.0));
+    assert_eq!(view.x_at(view.cursor), 0.0);
+    view.zoom(0.5);
+    assert_eq!((view.x_min, view.x_max), (-1.0, 1.0));
+    view.pan(0.5);
+    assert_eq!((view.x_min, view.x_max), (0.0, 2.0));
+    view.move_cursor(-1000);
+    assert_eq!(view.cursor, 0);
+    view.move_cursor(1000);
+    assert_eq!(view.x_at(view.cursor), 2.0);
+
+    let values = sample(&PlotView::new(-1.0, 1.0), |x| Some(1.0 / x));
+    assert_eq!(values.len(), SAMPLES + 1);
+    assert_eq!(values[SAMPLES / 2], None);
+    assert_eq!(y_bounds(&[Some(0.0), None, Some(10.0)]), [-0.5, 10.5]);
+    assert_eq!(y_bounds(&[Some(3.0)]), [2.0, 4.0]);
+    assert_eq!(y_bounds(&[None]), [-1.0, 1.0]);
+}
@@ -0,0 +1,108 @@
+use num::complex::Complex;
+
+const MAX_ITER: usize = 1000;
+
+pub fn eval(coeffs: &[Complex<f64>], x: Complex<f64>) -> Complex<f64> {
+    // ホーナー法 (係数は高次から)
+    coeffs
+        .iter()
+        .fold(Complex::new(0.0, 0.0), |acc, c| acc * x + c)
+}
+
+pub fn roots(coeffs: &[Complex<f64>]) -> Option<Vec<Complex<f64>>> {
+    // Durand-Kerner法で全ての根を求める (係数は高次から)
+    let start = coeffs.iter().position(|c| c.norm() != 0.0)?;
+    let coeffs = &coeffs[start..];
+    // 定数項が0の場合は根0をくくり出す
+    let end = coeffs.iter().rposition(|c| c.norm() != 0.0)? + 1;
+    let mut result = vec![Complex::new(0.0, 0.0); coeffs.len() - end];
+    let monic = coeffs[..end]
+        .iter()
+        .map(|c| c / coeffs[0])
+        .collect::<Vec<_>>();
+    let degree = monic.len() - 1;
+    if degree == 0 {
+        return Some(result);
+    }
+
+    // 初期値は根の上界(Cauchyの上界)の円周上に配置する
+    let bound = 1.0 + monic[1..].iter().map(|c| c.norm()).fold(0.0, f64::max);
+    let seed = Complex::new(0.4, 0.9);
+    let mut z = (0..degree)
+        .map(|k| seed.powu(k as u32) * bound / seed.norm().powi(k as i32))
+        .collect::<Vec<_>>();
+    for _ in 0..MAX_ITER {
+        let mut max_delta: f64 = 0.0;
+        for i in 0..degree {
+            let denominator = (0..degree)
+                .filter(|&j| j != i)
+                .fold(Complex::new(1.0, 0.0), |acc, j| acc * (z[i] - z[j]));
+            if denominator.norm() == 0.0 {
+                z[i] += Complex::new(1e-8, 1e-8);
+                continue;
+            }
+            let delta = eval(&monic, z[i]) / denominator;
+            z[i] -= delta;
+            max_delta = max_delta.max(delta.norm() / z[i].norm().max(1.0));
+        }
+        if max_delta < 1e-15 {
+            break;
+        }
+    }
+
+    // ニュートン法で仕上げ
+    let derivative = monic[..degree]
+        .iter()
+        .enumerate()
+        .map(|(i, c)| c * (degree - i) as f64)
+        .collect::<Vec<_>>();
+    for root in z.iter_mut() {
+        let slope = eval(&derivative, *root);
+        if slope.norm() > 0.0 {
+            let refined = *root - eval(&monic, *root) / slope;
+            if eval(&monic, refined).norm() < eval(&monic, *root).norm() {
+                *root = refined;
+            }
+        }
+    }
+    result.extend(z);
+    Some(result)
+}
+
+#[test]
+fn poly_test() {
+    let real = |values: &[f64]| {
+        values
+            .iter()
+            .map(|v| Complex::new(*v, 0.0))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        eval(&real(&[1.0, 0.0, -4.0]), Complex::new(3.0, 0.0)),
+        Complex::new(5.0, 0.0)
+    );
+
+    let mut found = roots(&real(&[1.0, -6.0, 11.0, -6.0])).unwrap();
+    found.sort_by(|a, b| a.re.total_cmp(&b.re));
+    for (root, expected) in found.iter().zip([1.0, 2.0, 3.0]) {
+        assert!((root - expected).norm() < 1e-12);
+    }
+
+    let found = roots(&real(&[1.0, 0.0, 1.0])).unwrap();
+    assert!(
+        found
+            .iter()
+            .all(|r| (r.norm() - 1.0).abs() < 1e-12 && r.re.abs() < 1e-12)
+    );
+
+    let found = roots(&real(&[0.0, 2.0, -4.0, 0.0])).unwrap();
+    assert_eq!(found.len(), 2);
+    assert!(found.iter().any(|r| r.norm() == 0.0));
+    assert!(found.iter().any(|r| (r - 2.0).norm() < 1e-12));
+
+    let coeffs = real(&[1.0, -3.0, 0.0, 2.0, 7.0, -1.0, 5.0]);
+    for root in roots(&coeffs).unwrap() {
+        assert!(eval(&coeffs, root).norm() < 1e-10);
+    }
+    assert!(roots(&real(&[0.0, 0.0])).is_none());
+}
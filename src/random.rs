@@ -0,0 +1,137 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 乱数生成器 (xoshiro256**)
+// seedを指定すれば同じ乱数列を再現できる。
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        // seed未指定の場合は現在時刻から初期化
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Rng::new(nanos)
+    }
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // splitmix64で内部状態を初期化
+        let mut sm = seed;
+        let mut next = || {
+            sm = sm.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = sm;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+        Rng {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    pub fn uniform(&mut self) -> f64 {
+        // [0, 1) の一様乱数 (上位53bitを使用)
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn below(&mut self, n: u64) -> u64 {
+        // [0, n) の整数。偏りが出ないよう範囲外は捨てる
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % n;
+            }
+        }
+    }
+
+    pub fn range_int(&mut self, a: i64, b: i64) -> i64 {
+        // [a, b] の整数
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        let span = high.abs_diff(low);
+        if span == u64::MAX {
+            return self.next_u64() as i64;
+        }
+        low.wrapping_add(self.below(span + 1) as i64)
+    }
+
+    pub fn normal(&mut self) -> f64 {
+        // Box-Muller法による標準正規乱数
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    pub fn dice(&mut self, count: u32, sides: u32) -> u64 {
+        // NdM: M面ダイスをN個振った合計
+        (0..count).map(|_| self.below(sides as u64) + 1).sum()
+    }
+}
+
+pub fn parse_dice(expression: &str) -> Option<(u32, u32)> {
+    // "3d6" -> (3, 6), "d20" -> (1, 20)
+    let (count, sides) = expression.split_once('d')?;
+    let count = if count.is_empty() {
+        1
+    } else if count.chars().all(|c| c.is_ascii_digit()) {
+        count.parse().ok()?
+    } else {
+        return None;
+    };
+    if !sides.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let sides = sides.parse().ok()?;
+    if (1..=10000).contains(&count) && sides >= 1 {
+        Some((count, sides))
+    } else {
+        None
+    }
+}
+
+#[test]
+fn random_test() {
+    let mut rng1 = Rng::new(42);
+    let mut rng2 = Rng::new(42);
+    let seq1 = (0..5).map(|_| rng1.next_u64()).collect::<Vec<_>>();
+    let seq2 = (0..5).map(|_| rng2.next_u64()).collect::<Vec<_>>();
+    assert_eq!(seq1, seq2);
+    assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+
+    let mut rng = Rng::new(7);
+    for _ in 0..1000 {
+        let u = rng.uniform();
+        assert!((0.0..1.0).contains(&u));
+        let n = rng.range_int(3, -2);
+        assert!((-2..=3).contains(&n));
+        let roll = rng.dice(3, 6);
+        assert!((3..=18).contains(&roll));
+    }
+    let mean = (0..20000).map(|_| rng.normal()).sum::<f64>() / 20000.0;
+    assert!(mean.abs() < 0.05);
+
+    assert_eq!(parse_dice("3d6"), Some((3, 6)));
+    assert_eq!(parse_dice("d20"), Some((1, 20)));
+    assert_eq!(parse_dice("3d"), None);
+    assert_eq!(parse_dice("0d6"), None);
+    assert_eq!(parse_dice("del"), None);
+    assert_eq!(parse_dice("2d-6"), None);
+}
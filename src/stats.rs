@@ -0,0 +1,244 @@
+// 統計データ(Σ+で蓄積するデータ)
+#[derive(Debug, Clone, Default)]
+pub struct StatData {
+    pub data: Vec<(f64, Option<f64>)>,
+}
+
+impl StatData {
+    pub fn push(&mut self, x: f64, y: Option<f64>) -> usize {
+        self.data.push((x, y));
+        self.data.len()
+    }
+
+    pub fn remove(&mut self, x: f64, y: Option<f64>) -> Option<usize> {
+        // Σ-で一致するデータを一件削除する
+        let index = self.data.iter().position(|&item| item == (x, y))?;
+        self.data.remove(index);
+        Some(self.data.len())
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn xs(&self) -> Vec<f64> {
+        self.data.iter().map(|(x, _)| *x).collect()
+    }
+
+    pub fn pairs(&self) -> Option<(Vec<f64>, Vec<f64>)> {
+        // 二変数データ(全件にyがある場合のみ)
+        let ys = self
+            .data
+            .iter()
+            .map(|(_, y)| *y)
+            .collect::<Option<Vec<_>>>()?;
+        Some((self.xs(), ys))
+    }
+}
+
+// 回帰モデルの列挙型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitModel {
+    Linear,
+    Exponential,
+    Logarithmic,
+    Power,
+}
+
+impl FitModel {
+    // 線形化のための変換 (x, y) -> (X, Y)
+    fn transform(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        match self {
+            FitModel::Linear => Some((x, y)),
+            FitModel::Exponential => (y > 0.0).then(|| (x, y.ln())),
+            FitModel::Logarithmic => (x > 0.0).then(|| (x.ln(), y)),
+            FitModel::Power => (x > 0.0 && y > 0.0).then(|| (x.ln(), y.ln())),
+        }
+    }
+}
+
+// 回帰結果
+// Linear: y = a + bx, Exponential: y = a·e^(bx)
+// Logarithmic: y = a + b·ln(x), Power: y = a·x^b
+#[derive(Debug, Clone, Copy)]
+pub struct Fit {
+    pub model: FitModel,
+    pub a: f64,
+    pub b: f64,
+    pub r: f64,
+}
+
+impl Fit {
+    pub fn r_squared(&self) -> f64 {
+        self.r * self.r
+    }
+
+    pub fn predict_y(&self, x: f64) -> f64 {
+        match self.model {
+            FitModel::Linear => self.a + self.b * x,
+            FitModel::Exponential => self.a * (self.b * x).exp(),
+            FitModel::Logarithmic => self.a + self.b * x.ln(),
+            FitModel::Power => self.a * x.powf(self.b),
+        }
+    }
+
+    pub fn predict_x(&self, y: f64) -> f64 {
+        match self.model {
+            FitModel::Linear => (y - self.a) / self.b,
+            FitModel::Exponential => (y / self.a).ln() / self.b,
+            FitModel::Logarithmic => ((y - self.a) / self.b).exp(),
+            FitModel::Power => (y / self.a).powf(1.0 / self.b),
+        }
+    }
+}
+
+pub fn fit(model: FitModel, xs: &[f64], ys: &[f64]) -> Option<Fit> {
+    // 最小二乗法。非線形モデルは対数変換して線形回帰する
+    if xs.len() != ys.len() || xs.len() < 2 {
+        return None;
+    }
+    let (tx, ty): (Vec<f64>, Vec<f64>) = xs
+        .iter()
+        .zip(ys)
+        .map(|(x, y)| model.transform(*x, *y))
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let mx = mean(&tx)?;
+    let my = mean(&ty)?;
+    let sxx: f64 = tx.iter().map(|x| (x - mx).powi(2)).sum();
+    let syy: f64 = ty.iter().map(|y| (y - my).powi(2)).sum();
+    let sxy: f64 = tx.iter().zip(&ty).map(|(x, y)| (x - mx) * (y - my)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let b = sxy / sxx;
+    let intercept = my - b * mx;
+    let a = match model {
+        FitModel::Linear | FitModel::Logarithmic => intercept,
+        FitModel::Exponential | FitModel::Power => intercept.exp(),
+    };
+    let r = if syy == 0.0 {
+        1.0
+    } else {
+        sxy / (sxx * syy).sqrt()
+    };
+    Some(Fit { model, a, b, r })
+}
+
+pub fn best_fit(xs: &[f64], ys: &[f64]) -> Option<Fit> {
+    // 相関係数の絶対値が最大のモデルを選ぶ
+    [
+        FitModel::Linear,
+        FitModel::Exponential,
+        FitModel::Logarithmic,
+        FitModel::Power,
+    ]
+    .into_iter()
+    .filter_map(|model| fit(model, xs, ys))
+    .max_by(|f1, f2| f1.r.abs().total_cmp(&f2.r.abs()))
+}
+
+pub fn mean(data: &[f64]) -> Option<f64> {
+    if data.is_empty() {
+        None
+    } else {
+        Some(data.iter().sum::<f64>() / data.len() as f64)
+    }
+}
+
+pub fn variance(data: &[f64], sample: bool) -> Option<f64> {
+    // 標本分散(n-1)と母分散(n)
+    let m = mean(data)?;
+    let n = data.len() as f64;
+    let divisor = if sample { n - 1.0 } else { n };
+    if divisor <= 0.0 {
+        return None;
+    }
+    Some(data.iter().map(|x| (x - m).powi(2)).sum::<f64>() / divisor)
+}
+
+pub fn std_dev(data: &[f64], sample: bool) -> Option<f64> {
+    variance(data, sample).map(f64::sqrt)
+}
+
+pub fn median(data: &[f64]) -> Option<f64> {
+    if data.is_empty() {
+        return None;
+    }
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+    } else {
+        Some(sorted[mid])
+    }
+}
+
+pub fn min(data: &[f64]) -> Option<f64> {
+    data.iter().copied().reduce(f64::min)
+}
+
+pub fn max(data: &[f64]) -> Option<f64> {
+    data.iter().copied().reduce(f64::max)
+}
+
+#[test]
+fn stats_test() {
+    let data = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+    assert_eq!(mean(&data), Some(5.0));
+    assert_eq!(variance(&data, false), Some(4.0));
+    assert_eq!(std_dev(&data, false), Some(2.0));
+    assert!((std_dev(&data, true).unwrap() - 2.13809).abs() < 0.0001);
+    assert_eq!(median(&data), Some(4.5));
+    assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+    assert_eq!(min(&data), Some(2.0));
+    assert_eq!(max(&data), Some(9.0));
+    assert_eq!(variance(&[1.0], true), None);
+    assert_eq!(mean(&[]), None);
+
+    let mut stat = StatData::default();
+    assert_eq!(stat.push(1.0, None), 1);
+    assert_eq!(stat.push(2.0, None), 2);
+    assert_eq!(stat.remove(1.0, None), Some(1));
+    assert_eq!(stat.remove(5.0, None), None);
+    assert_eq!(stat.xs(), vec![2.0]);
+    assert!(stat.pairs().is_none());
+}
+
+#[test]
+fn regression_test() {
+    let xs = [1.0, 2.0, 3.0, 4.0];
+    let linear = fit(FitModel::Linear, &xs, &[3.0, 5.0, 7.0, 9.0]).unwrap();
+    assert!((linear.a - 1.0).abs() < 1e-10);
+    assert!((linear.b - 2.0).abs() < 1e-10);
+    assert!((linear.r - 1.0).abs() < 1e-10);
+    assert!((linear.predict_x(11.0) - 5.0).abs() < 1e-10);
+
+    let ys = xs.map(|x: f64| 3.0 * (0.5 * x).exp());
+    let exponential = best_fit(&xs, &ys).unwrap();
+    assert_eq!(exponential.model, FitModel::Exponential);
+    assert!((exponential.a - 3.0).abs() < 1e-10);
+    assert!((exponential.b - 0.5).abs() < 1e-10);
+    assert!((exponential.predict_y(6.0) - 3.0 * 3.0_f64.exp()).abs() < 1e-8);
+
+    let ys = xs.map(|x: f64| 2.0 * x.powf(1.5));
+    let power = best_fit(&xs, &ys).unwrap();
+    assert_eq!(power.model, FitModel::Power);
+    assert!((power.predict_x(power.predict_y(2.5)) - 2.5).abs() < 1e-10);
+
+    let ys = xs.map(|x: f64| 1.0 + 4.0 * x.ln());
+    let logarithmic = fit(FitModel::Logarithmic, &xs, &ys).unwrap();
+    assert!((logarithmic.r_squared() - 1.0).abs() < 1e-10);
+    assert!(fit(FitModel::Linear, &[1.0, 1.0], &[2.0, 3.0]).is_none());
+    assert!(fit(FitModel::Exponential, &xs, &[1.0, -1.0, 2.0, 3.0]).is_none());
+}
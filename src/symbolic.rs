@@ -0,0 +1,687 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+// 記号式
+// 'x^2+1' のように入力した式を木として保持し、簡約・展開・微分・代入を行う。
+// 記号式の三角関数は角度モードによらず常にラジアンとして扱う。
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Sym {
+    Num(f64),
+    Var(String),
+    Neg(Box<Sym>),
+    Add(Box<Sym>, Box<Sym>),
+    Sub(Box<Sym>, Box<Sym>),
+    Mul(Box<Sym>, Box<Sym>),
+    Div(Box<Sym>, Box<Sym>),
+    Pow(Box<Sym>, Box<Sym>),
+    Func(String, Box<Sym>),
+}
+
+const FUNCTIONS: [&str; 17] = [
+    "sin", "cos", "tan", "asin", "acos", "atan", "sinh", "cosh", "tanh", "asinh", "acosh", "atanh",
+    "sqrt", "ln", "log", "exp", "abs",
+];
+
+// 演算子で式の木を組み立てる
+impl Add for Sym {
+    type Output = Sym;
+    fn add(self, other: Sym) -> Sym {
+        Sym::Add(Box::new(self), Box::new(other))
+    }
+}
+impl Sub for Sym {
+    type Output = Sym;
+    fn sub(self, other: Sym) -> Sym {
+        Sym::Sub(Box::new(self), Box::new(other))
+    }
+}
+impl Mul for Sym {
+    type Output = Sym;
+    fn mul(self, other: Sym) -> Sym {
+        Sym::Mul(Box::new(self), Box::new(other))
+    }
+}
+impl Div for Sym {
+    type Output = Sym;
+    fn div(self, other: Sym) -> Sym {
+        Sym::Div(Box::new(self), Box::new(other))
+    }
+}
+impl Neg for Sym {
+    type Output = Sym;
+    fn neg(self) -> Sym {
+        Sym::Neg(Box::new(self))
+    }
+}
+
+impl Sym {
+    pub fn pow(self, exponent: Sym) -> Sym {
+        Sym::Pow(Box::new(self), Box::new(exponent))
+    }
+
+    pub fn func(name: &str, arg: Sym) -> Sym {
+        Sym::Func(name.to_string(), Box::new(arg))
+    }
+
+    fn depends_on(&self, var: &str) -> bool {
+        match self {
+            Sym::Num(_) => false,
+            Sym::Var(name) => name == var,
+            Sym::Neg(a) | Sym::Func(_, a) => a.depends_on(var),
+            Sym::Add(a, b) | Sym::Sub(a, b) | Sym::Mul(a, b) | Sym::Div(a, b) | Sym::Pow(a, b) => {
+                a.depends_on(var) || b.depends_on(var)
+            }
+        }
+    }
+
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Option<f64>) -> Result<f64, String> {
+        // 変数はlookupから、なければ定数pi, eを探す
+        match self {
+            Sym::Num(v) => Ok(*v),
+            Sym::Var(name) => match (lookup(name), name.as_str()) {
+                (Some(v), _) => Ok(v),
+                (None, "pi") => Ok(std::f64::consts::PI),
+                (None, "e") => Ok(std::f64::consts::E),
+                (None, _) => Err(format!("Undefined variable {name}")),
+            },
+            Sym::Neg(a) => Ok(-a.eval(lookup)?),
+            Sym::Add(a, b) => Ok(a.eval(lookup)? + b.eval(lookup)?),
+            Sym::Sub(a, b) => Ok(a.eval(lookup)? - b.eval(lookup)?),
+            Sym::Mul(a, b) => Ok(a.eval(lookup)? * b.eval(lookup)?),
+            Sym::Div(a, b) => Ok(a.eval(lookup)? / b.eval(lookup)?),
+            Sym::Pow(a, b) => Ok(a.eval(lookup)?.powf(b.eval(lookup)?)),
+            Sym::Func(name, a) => {
+                apply(name, a.eval(lookup)?).ok_or(format!("Unknown function {name}"))
+            }
+        }
+    }
+
+    pub fn subst(&self, lookup: &dyn Fn(&str) -> Option<Sym>) -> Sym {
+        // 変数を値(または式)に置き換えて簡約する
+        fn replace(sym: &Sym, lookup: &dyn Fn(&str) -> Option<Sym>) -> Sym {
+            match sym {
+                Sym::Var(name) => lookup(name).unwrap_or_else(|| sym.clone()),
+                Sym::Num(_) => sym.clone(),
+                Sym::Neg(a) => -replace(a, lookup),
+                Sym::Add(a, b) => replace(a, lookup) + replace(b, lookup),
+                Sym::Sub(a, b) => replace(a, lookup) - replace(b, lookup),
+                Sym::Mul(a, b) => replace(a, lookup) * replace(b, lookup),
+                Sym::Div(a, b) => replace(a, lookup) / replace(b, lookup),
+                Sym::Pow(a, b) => replace(a, lookup).pow(replace(b, lookup)),
+                Sym::Func(name, a) => Sym::func(name, replace(a, lookup)),
+            }
+        }
+        replace(self, lookup).simplify()
+    }
+
+    pub fn derivative(&self, var: &str) -> Sym {
+        // 微分 (簡約はしない)
+        let num = |v: f64| Sym::Num(v);
+        match self {
+            Sym::Num(_) => num(0.0),
+            Sym::Var(name) => num(if name == var { 1.0 } else { 0.0 }),
+            Sym::Neg(a) => -a.derivative(var),
+            Sym::Add(a, b) => a.derivative(var) + b.derivative(var),
+            Sym::Sub(a, b) => a.derivative(var) - b.derivative(var),
+            Sym::Mul(a, b) => a.derivative(var) * *b.clone() + *a.clone() * b.derivative(var),
+            Sym::Div(a, b) => {
+                (a.derivative(var) * *b.clone() - *a.clone() * b.derivative(var))
+                    / b.as_ref().clone().pow(num(2.0))
+            }
+            Sym::Pow(a, b) => {
+                let (a, b) = (a.as_ref().clone(), b.as_ref().clone());
+                if !b.depends_on(var) {
+                    // (a^n)' = n a^(n-1) a'
+                    b.clone() * a.clone().pow(b - num(1.0)) * a.derivative(var)
+                } else {
+                    // (a^b)' = a^b (b' ln a + b a' / a)
+                    a.clone().pow(b.clone())
+                        * (b.derivative(var) * Sym::func("ln", a.clone())
+                            + b * a.derivative(var) / a)
+                }
+            }
+            Sym::Func(name, a) => {
+                let a = a.as_ref().clone();
+                let outer = match name.as_str() {
+                    "sin" => Sym::func("cos", a.clone()),
+                    "cos" => -Sym::func("sin", a.clone()),
+                    "tan" => num(1.0) / Sym::func("cos", a.clone()).pow(num(2.0)),
+                    "asin" => num(1.0) / Sym::func("sqrt", num(1.0) - a.clone().pow(num(2.0))),
+                    "acos" => -(num(1.0) / Sym::func("sqrt", num(1.0) - a.clone().pow(num(2.0)))),
+                    "atan" => num(1.0) / (num(1.0) + a.clone().pow(num(2.0))),
+                    "sinh" => Sym::func("cosh", a.clone()),
+                    "cosh" => Sym::func("sinh", a.clone()),
+                    "tanh" => num(1.0) / Sym::func("cosh", a.clone()).pow(num(2.0)),
+                    "asinh" => num(1.0) / Sym::func("sqrt", a.clone().pow(num(2.0)) + num(1.0)),
+                    "acosh" => num(1.0) / Sym::func("sqrt", a.clone().pow(num(2.0)) - num(1.0)),
+                    "atanh" => num(1.0) / (num(1.0) - a.clone().pow(num(2.0))),
+                    "sqrt" => num(1.0) / (num(2.0) * Sym::func("sqrt", a.clone())),
+                    "ln" => num(1.0) / a.clone(),
+                    "log" => num(1.0) / (a.clone() * Sym::func("ln", num(10.0))),
+                    "exp" => Sym::func("exp", a.clone()),
+                    _ => a.clone() / Sym::func("abs", a.clone()),
+                };
+                outer * a.derivative(var)
+            }
+        }
+    }
+
+    pub fn simplify(&self) -> Sym {
+        // 定数の計算、同類項・同じ底の累乗をまとめる
+        from_terms(to_terms(self, false))
+    }
+
+    pub fn expand(&self) -> Sym {
+        // 積と整数乗を展開してから同類項をまとめる
+        from_terms(to_terms(self, true))
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Sym::Add(..) | Sym::Sub(..) => 1,
+            Sym::Mul(..) | Sym::Div(..) => 2,
+            Sym::Neg(_) => 3,
+            Sym::Num(v) if *v < 0.0 => 3,
+            Sym::Pow(..) => 4,
+            _ => 5,
+        }
+    }
+}
+
+fn apply(name: &str, v: f64) -> Option<f64> {
+    Some(match name {
+        "sin" => v.sin(),
+        "cos" => v.cos(),
+        "tan" => v.tan(),
+        "asin" => v.asin(),
+        "acos" => v.acos(),
+        "atan" => v.atan(),
+        "sinh" => v.sinh(),
+        "cosh" => v.cosh(),
+        "tanh" => v.tanh(),
+        "asinh" => v.asinh(),
+        "acosh" => v.acosh(),
+        "atanh" => v.atanh(),
+        "sqrt" => v.sqrt(),
+        "ln" => v.ln(),
+        "log" => v.log10(),
+        "exp" => v.exp(),
+        "abs" => v.abs(),
+        _ => return None,
+    })
+}
+
+fn format_number(v: f64) -> String {
+    if v.fract() == 0.0 && v.abs() < 1e15 {
+        format!("{v:.0}")
+    } else {
+        format!("{v}")
+    }
+}
+
+impl fmt::Display for Sym {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // 優先順位がlevel未満の部分式は括弧で囲む
+        let wrap = |sym: &Sym, level: u8| match sym.precedence() < level {
+            true => format!("({sym})"),
+            false => sym.to_string(),
+        };
+        // 右辺の符号付きの項も括弧で囲む (a - (-b))
+        let rhs = |sym: &Sym, level: u8| match sym.precedence() == 3 {
+            true => format!("({sym})"),
+            false => wrap(sym, level),
+        };
+        match self {
+            Sym::Num(v) => write!(f, "{}", format_number(*v)),
+            Sym::Var(name) => write!(f, "{name}"),
+            Sym::Neg(a) => write!(f, "-{}", wrap(a, 2)),
+            Sym::Add(a, b) => write!(f, "{} + {}", a, rhs(b, 1)),
+            Sym::Sub(a, b) => write!(f, "{} - {}", a, rhs(b, 2)),
+            Sym::Mul(a, b) => write!(f, "{}*{}", wrap(a, 2), rhs(b, 2)),
+            Sym::Div(a, b) => write!(f, "{}/{}", wrap(a, 2), rhs(b, 4)),
+            Sym::Pow(a, b) => write!(f, "{}^{}", wrap(a, 5), wrap(b, 4)),
+            Sym::Func(name, a) => write!(f, "{name}({a})"),
+        }
+    }
+}
+
+// --式の解析--
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(char),
+}
+
+fn lex(text: &str) -> Result<Vec<Token>, String> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // 1e-3 のような指数表記
+            if i + 1 < chars.len()
+                && chars[i] == 'e'
+                && (chars[i + 1].is_ascii_digit()
+                    || (matches!(chars[i + 1], '+' | '-')
+                        && chars.get(i + 2).is_some_and(|c| c.is_ascii_digit())))
+            {
+                i += 2;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let number = chars[start..i].iter().collect::<String>();
+            tokens.push(Token::Num(
+                number
+                    .parse()
+                    .map_err(|_| format!("Invalid number {number}"))?,
+            ));
+        } else if c.is_alphabetic() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if "+-*/^()".contains(c) {
+            tokens.push(Token::Op(c));
+            i += 1;
+        } else {
+            return Err(format!("Unexpected character {c}"));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> Result<Sym, String> {
+        // expr = term (('+' | '-') term)*
+        let mut lhs = self.term()?;
+        loop {
+            if self.eat('+') {
+                lhs = lhs + self.term()?;
+            } else if self.eat('-') {
+                lhs = lhs - self.term()?;
+            } else {
+                return Ok(lhs);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Sym, String> {
+        // term = unary (('*' | '/' | 暗黙の積) unary)*
+        let mut lhs = self.unary()?;
+        loop {
+            if self.eat('*') {
+                lhs = lhs * self.unary()?;
+            } else if self.eat('/') {
+                lhs = lhs / self.unary()?;
+            } else if matches!(
+                self.peek(),
+                Some(Token::Num(_) | Token::Ident(_) | Token::Op('('))
+            ) {
+                lhs = lhs * self.power()?;
+            } else {
+                return Ok(lhs);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Sym, String> {
+        if self.eat('-') {
+            Ok(-self.unary()?)
+        } else if self.eat('+') {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<Sym, String> {
+        // 累乗は右結合
+        let base = self.atom()?;
+        if self.eat('^') {
+            Ok(base.pow(self.unary()?))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<Sym, String> {
+        match self.tokens.get(self.pos).cloned() {
+            Some(Token::Num(v)) => {
+                self.pos += 1;
+                Ok(Sym::Num(v))
+            }
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                if FUNCTIONS.contains(&name.as_str()) && self.eat('(') {
+                    let arg = self.expr()?;
+                    if !self.eat(')') {
+                        return Err("Missing )".to_string());
+                    }
+                    Ok(Sym::func(&name, arg))
+                } else {
+                    Ok(Sym::Var(name))
+                }
+            }
+            Some(Token::Op('(')) => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                if !self.eat(')') {
+                    return Err("Missing )".to_string());
+                }
+                Ok(inner)
+            }
+            _ => Err("Invalid expression".to_string()),
+        }
+    }
+}
+
+pub fn parse(text: &str) -> Result<Sym, String> {
+    let mut parser = Parser {
+        tokens: lex(text)?,
+        pos: 0,
+    };
+    let sym = parser.expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err("Invalid expression".to_string());
+    }
+    Ok(sym)
+}
+
+// --簡約--
+// 式を 係数 × Π(因子^指数) の和に分解してまとめ直す
+#[derive(Debug, Clone)]
+struct Term {
+    coeff: f64,
+    factors: Vec<(Sym, f64)>,
+}
+type Terms = Vec<Term>;
+
+fn constant(c: f64) -> Terms {
+    vec![Term {
+        coeff: c,
+        factors: vec![],
+    }]
+}
+
+fn atom(sym: Sym, exponent: f64) -> Terms {
+    match sym {
+        Sym::Num(c) => constant(c.powf(exponent)),
+        sym => vec![Term {
+            coeff: 1.0,
+            factors: vec![(sym, exponent)],
+        }],
+    }
+}
+
+fn as_constant(terms: &Terms) -> Option<f64> {
+    match terms.as_slice() {
+        [] => Some(0.0),
+        [term] if term.factors.is_empty() => Some(term.coeff),
+        _ => None,
+    }
+}
+
+fn scale(terms: Terms, c: f64) -> Terms {
+    terms
+        .into_iter()
+        .map(|term| Term {
+            coeff: term.coeff * c,
+            ..term
+        })
+        .collect()
+}
+
+fn multiply(a: Terms, b: Terms, distribute: bool) -> Terms {
+    // 展開しない場合、和どうしの積は和を一つの因子として扱う
+    let single = |terms: Terms| match terms.len() > 1 && !distribute {
+        true => atom(from_terms(terms), 1.0),
+        false => terms,
+    };
+    let (a, b) = match as_constant(&a).is_some() || as_constant(&b).is_some() {
+        true => (a, b),
+        false => (single(a), single(b)),
+    };
+    let mut product = Vec::new();
+    for x in &a {
+        for y in &b {
+            product.push(Term {
+                coeff: x.coeff * y.coeff,
+                factors: x.factors.iter().chain(&y.factors).cloned().collect(),
+            });
+        }
+    }
+    normalize(product)
+}
+
+fn power(base: Terms, n: f64, distribute: bool) -> Terms {
+    if n == 0.0 {
+        return constant(1.0);
+    }
+    if let Some(c) = as_constant(&base) {
+        return constant(c.powf(n));
+    }
+    match base.as_slice() {
+        [term] if n.fract() == 0.0 => vec![Term {
+            coeff: term.coeff.powf(n),
+            factors: term
+                .factors
+                .iter()
+                .map(|(sym, exponent)| (sym.clone(), exponent * n))
+                .collect(),
+        }],
+        _ if distribute && n.fract() == 0.0 && (1.0..=16.0).contains(&n) => {
+            let mut result = base.clone();
+            for _ in 1..n as usize {
+                result = multiply(result, base.clone(), true);
+            }
+            result
+        }
+        _ => atom(from_terms(base), n),
+    }
+}
+
+fn to_terms(sym: &Sym, distribute: bool) -> Terms {
+    let terms = match sym {
+        Sym::Num(c) => constant(*c),
+        Sym::Var(_) => atom(sym.clone(), 1.0),
+        Sym::Neg(a) => scale(to_terms(a, distribute), -1.0),
+        Sym::Add(a, b) => [to_terms(a, distribute), to_terms(b, distribute)].concat(),
+        Sym::Sub(a, b) => [
+            to_terms(a, distribute),
+            scale(to_terms(b, distribute), -1.0),
+        ]
+        .concat(),
+        Sym::Mul(a, b) => multiply(to_terms(a, distribute), to_terms(b, distribute), distribute),
+        Sym::Div(a, b) => {
+            let divisor = to_terms(b, distribute);
+            let reciprocal = match divisor.as_slice() {
+                [term] if term.coeff != 0.0 => vec![Term {
+                    coeff: 1.0 / term.coeff,
+                    factors: term
+                        .factors
+                        .iter()
+                        .map(|(sym, exponent)| (sym.clone(), -exponent))
+                        .collect(),
+                }],
+                _ => atom(from_terms(divisor), -1.0),
+            };
+            multiply(to_terms(a, distribute), reciprocal, distribute)
+        }
+        Sym::Pow(a, b) => match from_terms(to_terms(b, distribute)) {
+            Sym::Num(n) => power(to_terms(a, distribute), n, distribute),
+            exponent => atom(from_terms(to_terms(a, distribute)).pow(exponent), 1.0),
+        },
+        Sym::Func(name, a) => match from_terms(to_terms(a, distribute)) {
+            Sym::Num(v) if apply(name, v).is_some_and(f64::is_finite) => {
+                constant(apply(name, v).unwrap_or(v))
+            }
+            arg => atom(Sym::func(name, arg), 1.0),
+        },
+    };
+    normalize(terms)
+}
+
+fn factor_key(sym: &Sym) -> (bool, String) {
+    // 変数を先に並べる
+    (!matches!(sym, Sym::Var(_)), sym.to_string())
+}
+
+fn normalize(terms: Terms) -> Terms {
+    let mut result: Terms = Vec::new();
+    for term in terms {
+        let mut factors: Vec<(Sym, f64)> = Vec::new();
+        for (sym, exponent) in term.factors {
+            match factors.iter_mut().find(|(s, _)| *s == sym) {
+                Some((_, e)) => *e += exponent,
+                None => factors.push((sym, exponent)),
+            }
+        }
+        factors.retain(|(_, exponent)| *exponent != 0.0);
+        factors.sort_by_key(|(sym, _)| factor_key(sym));
+        match result.iter_mut().find(|t| t.factors == factors) {
+            Some(existing) => existing.coeff += term.coeff,
+            None => result.push(Term {
+                coeff: term.coeff,
+                factors,
+            }),
+        }
+    }
+    result.retain(|term| term.coeff != 0.0);
+    result
+}
+
+fn from_terms(mut terms: Terms) -> Sym {
+    // 次数の高い順に並べて式に戻す
+    let degree = |term: &Term| -> f64 {
+        term.factors
+            .iter()
+            .map(|(_, exponent)| exponent.max(0.0))
+            .sum()
+    };
+    terms.sort_by(|a, b| {
+        degree(b)
+            .total_cmp(&degree(a))
+            .then_with(|| factor_keys(a).cmp(&factor_keys(b)))
+    });
+    let product = |factors: Vec<Sym>| factors.into_iter().reduce(|acc, f| acc * f);
+    let raise = |sym: &Sym, exponent: f64| match exponent {
+        1.0 => sym.clone(),
+        _ => sym.clone().pow(Sym::Num(exponent)),
+    };
+    let mut result: Option<Sym> = None;
+    for term in terms {
+        let numerator = product(
+            term.factors
+                .iter()
+                .filter(|(_, e)| *e > 0.0)
+                .map(|(s, e)| raise(s, *e))
+                .collect(),
+        );
+        let denominator = product(
+            term.factors
+                .iter()
+                .filter(|(_, e)| *e < 0.0)
+                .map(|(s, e)| raise(s, -e))
+                .collect(),
+        );
+        let magnitude = term.coeff.abs();
+        let mut body = match numerator {
+            Some(n) if magnitude == 1.0 => n,
+            Some(n) => Sym::Num(magnitude) * n,
+            None => Sym::Num(magnitude),
+        };
+        if let Some(d) = denominator {
+            body = body / d;
+        }
+        result = Some(match (result, term.coeff < 0.0) {
+            (None, false) => body,
+            (None, true) => match body {
+                Sym::Num(v) => Sym::Num(-v),
+                body => -body,
+            },
+            (Some(acc), false) => acc + body,
+            (Some(acc), true) => acc - body,
+        });
+    }
+    result.unwrap_or(Sym::Num(0.0))
+}
+
+fn factor_keys(term: &Term) -> Vec<(bool, String)> {
+    term.factors.iter().map(|(s, _)| factor_key(s)).collect()
+}
+
+#[test]
+fn symbolic_test() {
+    let show = |text: &str| parse(text).unwrap().to_string();
+    let simplify = |text: &str| parse(text).unwrap().simplify().to_string();
+    let expand = |text: &str| parse(text).unwrap().expand().to_string();
+    let derive = |text: &str| parse(text).unwrap().derivative("x").simplify().to_string();
+
+    assert_eq!(show("x^2+1"), "x^2 + 1");
+    assert_eq!(show("-(a+b)*c"), "-(a + b)*c");
+    assert_eq!(show("2x^-1"), "2*x^(-1)");
+    assert_eq!(show("a-(b-c)"), "a - (b - c)");
+    assert_eq!(show("sin(x)/(2*y)"), "sin(x)/(2*y)");
+    assert!(parse("x^").is_err());
+    assert!(parse("(x+1").is_err());
+    assert!(parse("x $ 1").is_err());
+
+    assert_eq!(simplify("x + x + 0*y"), "2*x");
+    assert_eq!(simplify("x*x/x"), "x");
+    assert_eq!(simplify("2*3 + sin(0)"), "6");
+    assert_eq!(simplify("(x+1)*(x+1)"), "(x + 1)^2");
+    assert_eq!(simplify("x - x"), "0");
+    assert_eq!(simplify("y/x*3"), "3*y/x");
+    assert_eq!(expand("(x+1)^2"), "x^2 + 2*x + 1");
+    assert_eq!(expand("(a-b)*(a+b)"), "a^2 - b^2");
+
+    assert_eq!(derive("x^3 + 2*x"), "3*x^2 + 2");
+    assert_eq!(derive("x*sin(x)"), "x*cos(x) + sin(x)");
+    assert_eq!(derive("1/x"), "-1/x^2");
+    assert_eq!(derive("exp(2*x)"), "2*exp(2*x)");
+    assert_eq!(derive("y^2"), "0");
+
+    let sym = parse("x^2 + y").unwrap();
+    assert_eq!(
+        sym.eval(&|name| (name == "x").then_some(3.0)),
+        Err("Undefined variable y".to_string())
+    );
+    assert_eq!(
+        sym.subst(&|name| (name == "x").then_some(Sym::Num(3.0)))
+            .to_string(),
+        "y + 9"
+    );
+    assert_eq!(
+        parse("2*pi").unwrap().eval(&|_| None),
+        Ok(2.0 * std::f64::consts::PI)
+    );
+}